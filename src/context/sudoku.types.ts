@@ -41,9 +41,25 @@ export interface Elimination {
   value: number
 }
 
+/**
+ * Marks cause cells that play a special part in a pattern (e.g. the fins of a fish).
+ * `Color` numbers the coloring groups; colors `2k` and `2k + 1` are opposites.
+ * `Als` numbers the Almost Locked Sets of an ALS technique.
+ * Forcing chains form an implication tree: `Implied` and `Contradiction` hold the
+ * positions in `cause` of the cells that implied them, rooted at an `Assumption`.
+ */
+export type CauseRole =
+  | 'Fin'
+  | 'Assumption'
+  | { Color: number }
+  | { Als: number }
+  | { Implied: number[] }
+  | { Contradiction: number[] }
+
 export interface CauseCell {
   index: number
   candidates: number[]
+  role?: CauseRole
 }
 
 export interface SolvingStep {
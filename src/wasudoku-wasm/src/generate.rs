@@ -17,7 +17,7 @@
 */
 
 use crate::board::Board;
-use crate::logical_solver;
+use crate::logical_solver::{self, SolverConfig, TechniqueLevel};
 use crate::solver;
 use rand::rng;
 use rand::seq::SliceRandom;
@@ -85,7 +85,10 @@ fn create_minimal_puzzle_symmetric(solution: &Board, min_clues: Option<usize>) -
 
 /// Check if a puzzle matches the criteria for a specific difficulty.
 fn matches_difficulty(puzzle: &Board, difficulty: Difficulty) -> bool {
-    let (steps, solved_board) = logical_solver::solve_with_steps(puzzle);
+    // The Extreme techniques are too slow to run on every attempt, so they are only
+    // tried on Extreme candidates that the Master techniques cannot finish.
+    let config = SolverConfig::up_to(TechniqueLevel::Master);
+    let (steps, solved_board) = logical_solver::solve_with_config(puzzle, &config);
     let is_logically_solvable = solved_board.cells.iter().all(|&c| c != 0);
 
     let stats = logical_solver::analyze_difficulty(&steps);
@@ -93,18 +96,18 @@ fn matches_difficulty(puzzle: &Board, difficulty: Difficulty) -> bool {
     match difficulty {
         Difficulty::Easy => {
             // Must be solvable and only require Basic techniques
-            is_logically_solvable && stats.max_level == logical_solver::TechniqueLevel::Basic
+            is_logically_solvable && stats.max_level == TechniqueLevel::Basic
         }
         Difficulty::Medium => {
             // Must be solvable, meet minimum counts for steps, and not exceed Intermediate level
             is_logically_solvable
-                && stats.max_level == logical_solver::TechniqueLevel::Intermediate
+                && stats.max_level == TechniqueLevel::Intermediate
                 && stats.intermediate_count >= 5
         }
         Difficulty::Hard => {
             // Must be solvable, meet minimum counts for steps, and not exceed Advanced level
             is_logically_solvable
-                && stats.max_level == logical_solver::TechniqueLevel::Advanced
+                && stats.max_level == TechniqueLevel::Advanced
                 && stats.advanced_count >= 3
                 && stats.intermediate_count >= 5
         }
@@ -116,8 +119,13 @@ fn matches_difficulty(puzzle: &Board, difficulty: Difficulty) -> bool {
                 && stats.intermediate_count >= 5
         }
         Difficulty::Extreme => {
-            // Must NOT be solvable by pure logic (requires backtracking / guessing).
-            !is_logically_solvable
+            // Must NOT be solvable up to Master techniques, but must be solvable
+            // once the Extreme techniques (Exocet, templates, forcing chains) are allowed.
+            !is_logically_solvable && {
+                let (_, solved_board) =
+                    logical_solver::solve_with_config(puzzle, &SolverConfig::default());
+                solved_board.cells.iter().all(|&c| c != 0)
+            }
         }
     }
 }
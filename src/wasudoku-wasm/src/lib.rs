@@ -19,14 +19,16 @@
 pub mod board;
 pub mod generate;
 pub mod logical_solver;
+pub mod progress;
 pub mod solver;
 pub mod types;
 mod utils;
 
 use board::Board;
 use generate::Difficulty;
+use logical_solver::{LogicalBoard, SolverConfig, TechniqueLevel};
 use std::panic;
-use types::SolveResult;
+use types::{SolveResult, Technique};
 use wasm_bindgen::prelude::*;
 
 /// Set the panic hook to forward Rust panics to the browser console.
@@ -38,9 +40,10 @@ pub fn main() {
 /// Solve a Sudoku puzzle and return the logical steps and solution.
 ///
 /// This function employs a hybrid strategy. It first applies logical solving
-/// techniques to generate human-readable steps. If logic alone cannot solve
-/// the puzzle, it falls back to a high-speed backtracking algorithm to find
-/// the final solution.
+/// techniques to generate human-readable steps, resorting to the Extreme ones
+/// (Exocet, templates, forcing chains) only when nothing simpler applies. If
+/// logic alone cannot solve the puzzle, it falls back to a high-speed
+/// backtracking algorithm to find the final solution.
 ///
 /// ### Arguments
 ///
@@ -62,10 +65,204 @@ pub fn solve_sudoku(board_str: &str) -> Result<JsValue, JsValue> {
         .parse::<Board>()
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
+    solve_board(initial_board, SolverConfig::default())
+}
+
+/// Solve a Sudoku puzzle like `solve_sudoku`, but only with the given techniques.
+///
+/// ### Arguments
+///
+/// * `board_str` - An 81-character string representing the Sudoku board,
+///   with `.` or `0` for empty cells.
+/// * `techniques` - The ids of the enabled techniques (e.g. "NakedSingle"),
+///   in the order they should be tried. An empty list keeps the default set.
+/// * `max_level` - The hardest level allowed: "basic", "intermediate",
+///   "advanced", "master", or "extreme".
+///
+/// ### Returns
+///
+/// * A `JsValue` containing the serialized `SolveResult`. Whatever the allowed
+///   techniques cannot solve is completed by backtracking.
+///
+/// ### Errors
+///
+/// * A `JsValue` error if the board, a technique id or the level is invalid,
+///   the puzzle is unsolvable, or a panic occurs in the underlying solver.
+#[wasm_bindgen]
+pub fn solve_sudoku_with_config(
+    board_str: &str,
+    techniques: Vec<String>,
+    max_level: &str,
+) -> Result<JsValue, JsValue> {
+    let initial_board: Board = board_str
+        .parse::<Board>()
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    let max_level = match max_level {
+        "basic" => TechniqueLevel::Basic,
+        "intermediate" => TechniqueLevel::Intermediate,
+        "advanced" => TechniqueLevel::Advanced,
+        "master" => TechniqueLevel::Master,
+        "extreme" => TechniqueLevel::Extreme,
+        _ => return Err(JsValue::from_str("Invalid technique level.")),
+    };
+    let mut config = SolverConfig::up_to(max_level);
+    if !techniques.is_empty() {
+        config.techniques = techniques
+            .iter()
+            .map(|id| {
+                Technique::from_id(id)
+                    .ok_or_else(|| JsValue::from_str(&format!("Unknown technique: {}", id)))
+            })
+            .collect::<Result<_, _>>()?;
+    }
+
+    solve_board(initial_board, config)
+}
+
+/// Find the simplest logical step from the player's current state.
+///
+/// ### Arguments
+///
+/// * `board_str` - An 81-character string representing the Sudoku board,
+///   with `.` or `0` for empty cells.
+/// * `candidates` - The player's pencil marks: 81 masks where bit `d - 1`
+///   stands for digit `d`. A cell without marks gets every candidate.
+///
+/// ### Returns
+///
+/// * A `JsValue` containing the serialized `SolvingStep`, or `null` if no
+///   technique applies.
+///
+/// ### Errors
+///
+/// * A `JsValue` error if the input is invalid, the pencil marks leave a cell
+///   or a digit of a unit without a place, or a panic occurs in the underlying
+///   solver.
+#[wasm_bindgen]
+pub fn get_hint(board_str: &str, candidates: Vec<u16>) -> Result<JsValue, JsValue> {
+    let board: Board = board_str
+        .parse::<Board>()
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let masks: [u16; 81] = candidates
+        .try_into()
+        .map_err(|_| JsValue::from_str("Expected 81 candidate masks."))?;
+
+    let logical_board =
+        LogicalBoard::from_candidates(&board, &masks).map_err(|e| JsValue::from_str(&e))?;
+
+    let result = panic::catch_unwind(move || {
+        logical_solver::find_next_step(&logical_board, &SolverConfig::default())
+    });
+
+    match result {
+        Ok(Some(step)) => Ok(serde_wasm_bindgen::to_value(&step).unwrap()),
+        Ok(None) => Ok(JsValue::NULL),
+        Err(_) => Err(JsValue::from_str("Solver crashed due to a critical error.")),
+    }
+}
+
+/// Check the player's entries and pencil marks against the puzzle's solution.
+///
+/// ### Arguments
+///
+/// * `puzzle_str` - An 81-character string with the puzzle's givens.
+/// * `entries` - The 81 digits on the player's board, `0` for empty cells.
+/// * `candidates` - The player's pencil marks: 81 masks where bit `d - 1`
+///   stands for digit `d`. Cells without marks are not checked.
+///
+/// ### Returns
+///
+/// * A `JsValue` containing the serialized `ProgressReport`.
+///
+/// ### Errors
+///
+/// * A `JsValue` error if the input is invalid, the puzzle does not have a
+///   unique solution, or a panic occurs in the underlying solver.
+#[wasm_bindgen]
+pub fn check_progress(
+    puzzle_str: &str,
+    entries: Vec<u8>,
+    candidates: Vec<u16>,
+) -> Result<JsValue, JsValue> {
+    let puzzle: Board = puzzle_str
+        .parse::<Board>()
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let entries: [u8; 81] = entries
+        .try_into()
+        .map_err(|_| JsValue::from_str("Expected 81 entries."))?;
+    let masks: [u16; 81] = candidates
+        .try_into()
+        .map_err(|_| JsValue::from_str("Expected 81 candidate masks."))?;
+
+    let result = panic::catch_unwind(move || progress::check_progress(&puzzle, &entries, &masks));
+
+    match result {
+        Ok(Ok(report)) => Ok(serde_wasm_bindgen::to_value(&report).unwrap()),
+        Ok(Err(e)) => Err(JsValue::from_str(&e)),
+        Err(_) => Err(JsValue::from_str(
+            "Progress check crashed due to a critical error.",
+        )),
+    }
+}
+
+/// Explain why a digit cannot go in a cell, from the player's current state.
+///
+/// ### Arguments
+///
+/// * `board_str` - An 81-character string representing the Sudoku board,
+///   with `.` or `0` for empty cells.
+/// * `candidates` - The player's pencil marks: 81 masks where bit `d - 1`
+///   stands for digit `d`. A cell without marks gets every candidate.
+/// * `index` - The cell, from 0 to 80, row by row.
+/// * `value` - The digit, from 1 to 9.
+///
+/// ### Returns
+///
+/// * A `JsValue` containing the serialized `CandidateExplanation`: `"Correct"`
+///   if the digit is the cell's solution, the eliminating step, or
+///   `"Unexplained"`.
+///
+/// ### Errors
+///
+/// * A `JsValue` error if the input is invalid, the pencil marks leave a cell
+///   or a digit of a unit without a place, the digit is not a candidate of the
+///   cell, or a panic occurs in the underlying solver.
+#[wasm_bindgen]
+pub fn explain_candidate(
+    board_str: &str,
+    candidates: Vec<u16>,
+    index: usize,
+    value: u8,
+) -> Result<JsValue, JsValue> {
+    let board: Board = board_str
+        .parse::<Board>()
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let masks: [u16; 81] = candidates
+        .try_into()
+        .map_err(|_| JsValue::from_str("Expected 81 candidate masks."))?;
+
+    let logical_board =
+        LogicalBoard::from_candidates(&board, &masks).map_err(|e| JsValue::from_str(&e))?;
+
+    let result = panic::catch_unwind(move || {
+        logical_solver::explain::explain_candidate(&logical_board, index, value)
+    });
+
+    match result {
+        Ok(Ok(explanation)) => Ok(serde_wasm_bindgen::to_value(&explanation).unwrap()),
+        Ok(Err(e)) => Err(JsValue::from_str(&e)),
+        Err(_) => Err(JsValue::from_str("Solver crashed due to a critical error.")),
+    }
+}
+
+/// Solves the board with `config`, falling back to backtracking, and serializes the result.
+fn solve_board(initial_board: Board, config: SolverConfig) -> Result<JsValue, JsValue> {
     // Use `catch_unwind` to contain any panics within the solver logic,
     // preventing the WASM module from crashing and allowing a graceful error return.
     let solve_result = panic::catch_unwind(move || {
-        let (steps, mut board_after_logic) = logical_solver::solve_with_steps(&initial_board);
+        let (steps, mut board_after_logic) =
+            logical_solver::solve_with_config(&initial_board, &config);
 
         // If logic was not sufficient, fall back to the backtracking algorithm.
         let end_solution = if board_after_logic.cells.contains(&0) {
@@ -0,0 +1,400 @@
+/*
+* Copyright (C) 2025-2026  Henrique Almeida
+* This file is part of WASudoku.
+*
+* WASudoku is free software: you can redistribute it and/or modify
+* it under the terms of the GNU Affero General Public License as published
+* by the Free Software Foundation, either version 3 of the License, or
+* (at your option) any later version.
+*
+* WASudoku is distributed in the hope that it will be useful,
+* but WITHOUT ANY WARRANTY; without even the implied warranty of
+* MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+* GNU Affero General Public License for more details.
+*
+* You should have received a copy of the GNU Affero General Public License
+* along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Alternating Inference Chains (AIC).
+//! Nodes are a digit in a single cell or in a box-line segment (grouped node).
+//! Chains alternate strong and weak links across digits and cells.
+
+use super::basic::collect_placement_eliminations;
+use super::wings::are_peers;
+use super::{ALL_UNITS, BOX_UNITS, LogicalBoard, PEER_MAP, mask_to_vec};
+use crate::types::{CauseCell, Elimination, Placement, SolvingStep, Technique};
+use std::collections::VecDeque;
+
+/// A chain node: the statement "`digit` is in one of `cells`".
+pub(super) struct ChainNode {
+    pub cells: Vec<usize>,
+    pub digit: u8,
+    /// Bitmask of `cells`, used for fast disjointness and coverage checks.
+    pub cell_mask: u128,
+}
+
+/// The link graph over all single and grouped nodes on the board.
+pub(super) struct ChainGraph {
+    pub nodes: Vec<ChainNode>,
+    pub strong: Vec<Vec<usize>>,
+    pub weak: Vec<Vec<usize>>,
+}
+
+/// Searches for the shortest productive Alternating Inference Chain.
+///
+/// * Type 1: both ends are the same digit, eliminate it from cells seeing both ends.
+/// * Type 2: ends are different digits in cells that see each other (or the same cell).
+/// * Discontinuous Nice Loop: a chain from a candidate being false back to it being
+///   true, which places the candidate.
+pub fn find_aic(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let graph = build_chain_graph(board);
+    let mut best: Option<(usize, SolvingStep)> = None;
+
+    for start in 0..graph.nodes.len() {
+        if graph.strong[start].is_empty() {
+            continue;
+        }
+        let max_len = best.as_ref().map_or(usize::MAX, |(len, _)| *len);
+        if let Some((len, step)) = search_aic_from(board, &graph, start, max_len, accepts) {
+            best = Some((len, step));
+        }
+    }
+    best.map(|(_, step)| step)
+}
+
+/// Builds every single-cell and grouped node together with their strong and weak links.
+pub(super) fn build_chain_graph(board: &LogicalBoard) -> ChainGraph {
+    let mut nodes = Vec::new();
+
+    for idx in 0..81 {
+        if board.cells[idx] == 0 {
+            for digit in mask_to_vec(board.candidates[idx]) {
+                nodes.push(ChainNode {
+                    cells: vec![idx],
+                    digit,
+                    cell_mask: 1 << idx,
+                });
+            }
+        }
+    }
+    push_group_nodes(board, &mut nodes);
+
+    let mut strong = vec![Vec::new(); nodes.len()];
+    let mut weak = vec![Vec::new(); nodes.len()];
+
+    for i in 0..nodes.len() {
+        for j in (i + 1)..nodes.len() {
+            let (a, b) = (&nodes[i], &nodes[j]);
+            let linked = if a.digit == b.digit {
+                a.cell_mask & b.cell_mask == 0 && share_unit(a.cell_mask | b.cell_mask)
+            } else {
+                // Different digits in the same cell can never both be true.
+                a.cells.len() == 1 && a.cell_mask == b.cell_mask
+            };
+            if linked {
+                weak[i].push(j);
+                weak[j].push(i);
+            }
+        }
+    }
+
+    add_unit_strong_links(board, &nodes, &mut strong);
+    add_bivalue_strong_links(board, &nodes, &mut strong);
+
+    ChainGraph {
+        nodes,
+        strong,
+        weak,
+    }
+}
+
+/// Adds a grouped node for every box-line segment holding a digit in two or three cells.
+fn push_group_nodes(board: &LogicalBoard, nodes: &mut Vec<ChainNode>) {
+    for box_unit in BOX_UNITS.iter() {
+        for digit in 1..=9u8 {
+            let mask = 1 << (digit - 1);
+            let cells: Vec<usize> = box_unit
+                .iter()
+                .filter(|&&i| board.cells[i] == 0 && (board.candidates[i] & mask) != 0)
+                .cloned()
+                .collect();
+
+            for line in 0..3 {
+                let row_segment: Vec<usize> = cells
+                    .iter()
+                    .filter(|&&i| i / 9 == box_unit[line * 3] / 9)
+                    .cloned()
+                    .collect();
+                let col_segment: Vec<usize> = cells
+                    .iter()
+                    .filter(|&&i| i % 9 == box_unit[line] % 9)
+                    .cloned()
+                    .collect();
+
+                for segment in [row_segment, col_segment] {
+                    if segment.len() >= 2 {
+                        let cell_mask = segment.iter().fold(0u128, |m, &i| m | (1 << i));
+                        nodes.push(ChainNode {
+                            cells: segment,
+                            digit,
+                            cell_mask,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Two nodes are strongly linked if together they hold every position of their digit in a unit.
+fn add_unit_strong_links(board: &LogicalBoard, nodes: &[ChainNode], strong: &mut [Vec<usize>]) {
+    for unit in ALL_UNITS.iter() {
+        let unit_mask = unit.iter().fold(0u128, |m, &i| m | (1 << i));
+
+        for digit in 1..=9u8 {
+            let mask = 1 << (digit - 1);
+            let positions = unit
+                .iter()
+                .filter(|&&i| board.cells[i] == 0 && (board.candidates[i] & mask) != 0)
+                .fold(0u128, |m, &i| m | (1 << i));
+
+            if positions.count_ones() < 2 {
+                continue;
+            }
+
+            let in_unit: Vec<usize> = (0..nodes.len())
+                .filter(|&n| nodes[n].digit == digit && nodes[n].cell_mask & !unit_mask == 0)
+                .collect();
+
+            for (k, &a) in in_unit.iter().enumerate() {
+                for &b in &in_unit[(k + 1)..] {
+                    let (ma, mb) = (nodes[a].cell_mask, nodes[b].cell_mask);
+                    if ma & mb == 0 && ma | mb == positions && !strong[a].contains(&b) {
+                        strong[a].push(b);
+                        strong[b].push(a);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The two candidates of a bivalue cell are strongly linked.
+fn add_bivalue_strong_links(board: &LogicalBoard, nodes: &[ChainNode], strong: &mut [Vec<usize>]) {
+    for i in 0..nodes.len() {
+        let node = &nodes[i];
+        if node.cells.len() != 1 || board.candidates[node.cells[0]].count_ones() != 2 {
+            continue;
+        }
+        for j in (i + 1)..nodes.len() {
+            if nodes[j].cells.len() == 1 && nodes[j].cell_mask == node.cell_mask {
+                strong[i].push(j);
+                strong[j].push(i);
+            }
+        }
+    }
+}
+
+/// Checks whether all cells in the mask lie in a single row, column or box, which holds
+/// exactly when every pair of them are peers.
+#[inline]
+fn share_unit(cell_mask: u128) -> bool {
+    let mut remaining = cell_mask;
+    while remaining != 0 {
+        let cell = remaining.trailing_zeros() as usize;
+        remaining &= remaining - 1;
+        if PEER_MAP[cell]
+            .iter()
+            .fold(remaining, |rest, &peer| rest & !(1 << peer))
+            != 0
+        {
+            return false;
+        }
+    }
+    true
+}
+
+/// Breadth-first search from `start` assumed false. Returns the chain length and the step
+/// for the first productive chain shorter than `max_len`.
+///
+/// Each node can be visited once as false and once as true, so a chain may pass through
+/// the partners of the start node again on its way back to it.
+fn search_aic_from(
+    board: &LogicalBoard,
+    graph: &ChainGraph,
+    start: usize,
+    max_len: usize,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<(usize, SolvingStep)> {
+    // States are indexed as `node * 2 + is_on`.
+    let mut parent: Vec<Option<usize>> = vec![None; graph.nodes.len() * 2];
+    let mut visited = vec![false; graph.nodes.len() * 2];
+    let mut queue = VecDeque::new();
+
+    visited[start * 2] = true;
+    queue.push_back((start, false, 1));
+
+    while let Some((node, is_on, len)) = queue.pop_front() {
+        if len >= max_len {
+            break;
+        }
+        let state = node * 2 + is_on as usize;
+
+        if is_on {
+            if len >= 4
+                && let Some(step) = check_aic_ends(board, graph, &parent, start, state, accepts)
+            {
+                return Some((len, step));
+            }
+            for &next in &graph.weak[node] {
+                if !visited[next * 2] {
+                    visited[next * 2] = true;
+                    parent[next * 2] = Some(state);
+                    queue.push_back((next, false, len + 1));
+                }
+            }
+        } else {
+            for &next in &graph.strong[node] {
+                if next == start
+                    && len >= 3
+                    && len + 1 < max_len
+                    && graph.nodes[start].cells.len() == 1
+                {
+                    // The start candidate being false forces it to be true.
+                    let chain = reconstruct_nodes(&parent, start * 2, state);
+                    let (idx, digit) = (graph.nodes[start].cells[0], graph.nodes[start].digit);
+                    let step = SolvingStep {
+                        technique: Technique::DiscontinuousNiceLoop,
+                        placements: vec![Placement {
+                            index: idx,
+                            value: digit,
+                        }],
+                        eliminations: collect_placement_eliminations(board, idx, digit),
+                        cause: build_chain_cause(graph, &chain),
+                    };
+                    if accepts(&step) {
+                        return Some((len + 1, step));
+                    }
+                }
+                if !visited[next * 2 + 1] {
+                    visited[next * 2 + 1] = true;
+                    parent[next * 2 + 1] = Some(state);
+                    queue.push_back((next, true, len + 1));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Given a chain proving that `start` or `end` is true, collects its eliminations.
+fn check_aic_ends(
+    board: &LogicalBoard,
+    graph: &ChainGraph,
+    parent: &[Option<usize>],
+    start: usize,
+    end_state: usize,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let end = end_state / 2;
+    let (s, e) = (&graph.nodes[start], &graph.nodes[end]);
+    let mut eliminations = Vec::new();
+
+    let technique = if s.digit == e.digit {
+        let mask = 1 << (s.digit - 1);
+        for idx in 0..81 {
+            if board.cells[idx] == 0
+                && (board.candidates[idx] & mask) != 0
+                && (s.cell_mask | e.cell_mask) & (1 << idx) == 0
+                && s.cells
+                    .iter()
+                    .chain(e.cells.iter())
+                    .all(|&c| are_peers(idx, c))
+            {
+                eliminations.push(Elimination {
+                    index: idx,
+                    value: s.digit,
+                });
+            }
+        }
+        Technique::AicType1
+    } else if s.cells.len() == 1 && e.cells.len() == 1 {
+        let (a, b) = (s.cells[0], e.cells[0]);
+        if a == b {
+            // The cell must be one of the two end digits.
+            let keep = (1 << (s.digit - 1)) | (1 << (e.digit - 1));
+            for value in mask_to_vec(board.candidates[a] & !keep) {
+                eliminations.push(Elimination { index: a, value });
+            }
+        } else if are_peers(a, b) {
+            if board.candidates[a] & (1 << (e.digit - 1)) != 0 {
+                eliminations.push(Elimination {
+                    index: a,
+                    value: e.digit,
+                });
+            }
+            if board.candidates[b] & (1 << (s.digit - 1)) != 0 {
+                eliminations.push(Elimination {
+                    index: b,
+                    value: s.digit,
+                });
+            }
+        }
+        Technique::AicType2
+    } else {
+        return None;
+    };
+
+    if eliminations.is_empty() {
+        return None;
+    }
+
+    let chain = reconstruct_nodes(parent, start * 2, end_state);
+    Some(SolvingStep {
+        technique,
+        placements: vec![],
+        eliminations,
+        cause: build_chain_cause(graph, &chain),
+    })
+    .filter(|step| accepts(step))
+}
+
+/// Walks parent links back from the `end` state to the `start` state,
+/// returning the node chain in order.
+pub(super) fn reconstruct_nodes(parent: &[Option<usize>], start: usize, end: usize) -> Vec<usize> {
+    let mut chain = vec![end / 2];
+    let mut current = end;
+    while current != start {
+        current = parent[current].expect("chain nodes always have a parent");
+        chain.push(current / 2);
+    }
+    chain.reverse();
+    chain
+}
+
+/// Expands a node chain into cause cells, merging consecutive nodes in the same cell.
+pub(super) fn build_chain_cause(graph: &ChainGraph, chain: &[usize]) -> Vec<CauseCell> {
+    let mut cause: Vec<CauseCell> = Vec::new();
+    for &n in chain {
+        let node = &graph.nodes[n];
+        for &idx in &node.cells {
+            match cause.last_mut() {
+                Some(last) if last.index == idx && node.cells.len() == 1 => {
+                    if !last.candidates.contains(&node.digit) {
+                        last.candidates.push(node.digit);
+                    }
+                }
+                _ => cause.push(CauseCell {
+                    index: idx,
+                    candidates: vec![node.digit],
+                    role: None,
+                }),
+            }
+        }
+    }
+    cause
+}
@@ -0,0 +1,411 @@
+/*
+* Copyright (C) 2025-2026  Henrique Almeida
+* This file is part of WASudoku.
+*
+* WASudoku is free software: you can redistribute it and/or modify
+* it under the terms of the GNU Affero General Public License as published
+* by the Free Software Foundation, either version 3 of the License, or
+* (at your option) any later version.
+*
+* WASudoku is distributed in the hope that it will be useful,
+* but WITHOUT ANY WARRANTY; without even the implied warranty of
+* MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+* GNU Affero General Public License for more details.
+*
+* You should have received a copy of the GNU Affero General Public License
+* along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Almost Locked Set (ALS) techniques: ALS-XZ, ALS-XY-Wing and Death Blossom.
+//! An ALS is a group of N unsolved cells in one unit holding exactly N + 1 candidates.
+
+use super::{ALL_UNITS, LogicalBoard, PEER_MAP, mask_to_vec};
+use crate::types::{CauseCell, CauseRole, Elimination, SolvingStep, Technique};
+
+/// An Almost Locked Set with per-digit positions and common peers.
+struct Als {
+    /// Bitset of the cells in the set.
+    cells: u128,
+    indices: Vec<usize>,
+    candidates: u16,
+    /// Bitset of the cells holding each digit (index `digit - 1`).
+    digit_cells: [u128; 9],
+    /// Bitset of the cells outside the set that see every cell holding each digit.
+    buddies: [u128; 9],
+}
+
+/// Board-wide data shared by the ALS finders.
+struct AlsContext {
+    sets: Vec<Als>,
+    /// Bitset of the cells holding each digit (index `digit - 1`).
+    digit_cells: [u128; 9],
+    peer_masks: [u128; 81],
+}
+
+impl AlsContext {
+    fn new(board: &LogicalBoard) -> Self {
+        let mut peer_masks = [0u128; 81];
+        for (idx, mask) in peer_masks.iter_mut().enumerate() {
+            *mask = PEER_MAP[idx].iter().fold(0, |acc, &p| acc | (1 << p));
+        }
+        let mut digit_cells = [0u128; 9];
+        for idx in 0..81 {
+            if board.cells[idx] == 0 {
+                for value in mask_to_vec(board.candidates[idx]) {
+                    digit_cells[(value - 1) as usize] |= 1 << idx;
+                }
+            }
+        }
+        let sets = collect_als(board, &peer_masks);
+        AlsContext {
+            sets,
+            digit_cells,
+            peer_masks,
+        }
+    }
+
+    /// Returns the restricted common candidates of two disjoint sets: digits whose
+    /// positions in both sets all see each other, so at most one set can hold them.
+    fn restricted_commons(&self, a: &Als, b: &Als) -> u16 {
+        if a.cells & b.cells != 0 {
+            return 0;
+        }
+        let mut rccs = 0;
+        for d in mask_to_vec(a.candidates & b.candidates) {
+            let i = (d - 1) as usize;
+            if b.digit_cells[i] & !a.buddies[i] == 0 {
+                rccs |= 1 << i;
+            }
+        }
+        rccs
+    }
+
+    /// Cells outside `exclude` holding `digit` that see all of its positions in `sets`.
+    fn common_buddies(&self, sets: &[&Als], digit: u8, exclude: u128) -> u128 {
+        let i = (digit - 1) as usize;
+        sets.iter()
+            .fold(self.digit_cells[i] & !exclude, |acc, als| {
+                acc & als.buddies[i]
+            })
+    }
+}
+
+/// Enumerates every ALS of every unit, without duplicates.
+fn collect_als(board: &LogicalBoard, peer_masks: &[u128; 81]) -> Vec<Als> {
+    let mut sets: Vec<Als> = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+
+    for unit in ALL_UNITS.iter() {
+        let empty: Vec<usize> = unit
+            .iter()
+            .filter(|&&idx| board.cells[idx] == 0)
+            .cloned()
+            .collect();
+
+        for subset in 1u32..(1 << empty.len()) {
+            let indices: Vec<usize> = (0..empty.len())
+                .filter(|&i| subset & (1 << i) != 0)
+                .map(|i| empty[i])
+                .collect();
+            let candidates = indices.iter().fold(0, |acc, &i| acc | board.candidates[i]);
+            if candidates.count_ones() as usize != indices.len() + 1 {
+                continue;
+            }
+            let cells = indices.iter().fold(0u128, |acc, &i| acc | (1 << i));
+            if !seen.insert(cells) {
+                continue;
+            }
+
+            let mut digit_cells = [0u128; 9];
+            let mut buddies = [0u128; 9];
+            for d in 0..9 {
+                let positions: Vec<usize> = indices
+                    .iter()
+                    .filter(|&&i| board.candidates[i] & (1 << d) != 0)
+                    .cloned()
+                    .collect();
+                if positions.is_empty() {
+                    continue;
+                }
+                digit_cells[d] = positions.iter().fold(0, |acc, &i| acc | (1 << i));
+                buddies[d] = positions.iter().fold(!cells, |acc, &i| acc & peer_masks[i]);
+            }
+
+            sets.push(Als {
+                cells,
+                indices,
+                candidates,
+                digit_cells,
+                buddies,
+            });
+        }
+    }
+    sets
+}
+
+// --- ALS-XZ ---
+
+/// Searches for ALS-XZ: two sets A and B joined by a restricted common candidate X.
+/// X is in at most one of them, so the other is locked; any other common digit Z must
+/// then appear in A or B, and is eliminated from cells seeing all of its positions.
+/// When two restricted commons link the sets (doubly linked), both sets are locked:
+/// every other digit of each set is eliminated from cells seeing all its positions,
+/// and each restricted common from cells seeing it in both sets.
+pub fn find_als_xz(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let ctx = AlsContext::new(board);
+
+    for (i, a) in ctx.sets.iter().enumerate() {
+        for b in &ctx.sets[i + 1..] {
+            let rccs = ctx.restricted_commons(a, b);
+            if rccs == 0 {
+                continue;
+            }
+            let exclude = a.cells | b.cells;
+            let mut targets = Vec::new();
+
+            if rccs.count_ones() == 1 {
+                for z in mask_to_vec(a.candidates & b.candidates & !rccs) {
+                    collect_targets(&mut targets, ctx.common_buddies(&[a, b], z, exclude), z);
+                }
+                if targets.is_empty() {
+                    continue;
+                }
+                let step = construct_als_step(Technique::AlsXz, board, &[a, b], None, targets);
+                if accepts(&step) {
+                    return Some(step);
+                }
+            } else {
+                for x in mask_to_vec(rccs) {
+                    collect_targets(&mut targets, ctx.common_buddies(&[a, b], x, exclude), x);
+                }
+                for als in [a, b] {
+                    for z in mask_to_vec(als.candidates & !rccs) {
+                        collect_targets(&mut targets, ctx.common_buddies(&[als], z, exclude), z);
+                    }
+                }
+                if targets.is_empty() {
+                    continue;
+                }
+                let step =
+                    construct_als_step(Technique::DoublyLinkedAlsXz, board, &[a, b], None, targets);
+                if accepts(&step) {
+                    return Some(step);
+                }
+            }
+        }
+    }
+    None
+}
+
+// --- ALS-XY-Wing ---
+
+/// Searches for ALS-XY-Wing: a pivot set C sharing restricted common X with A and a
+/// different restricted common Y with B. Either A or B is locked, so a digit Z common
+/// to A and B (other than X and Y) is eliminated from cells seeing all of its positions.
+pub fn find_als_xy_wing(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let ctx = AlsContext::new(board);
+
+    for c in &ctx.sets {
+        let wings: Vec<(&Als, u16)> = ctx
+            .sets
+            .iter()
+            .map(|other| (other, ctx.restricted_commons(c, other)))
+            .filter(|&(_, rccs)| rccs != 0)
+            .collect();
+
+        for (i, &(a, rcc_a)) in wings.iter().enumerate() {
+            for &(b, rcc_b) in &wings[i + 1..] {
+                if a.cells & b.cells != 0 {
+                    continue;
+                }
+                let exclude = a.cells | b.cells | c.cells;
+                let mut targets = Vec::new();
+                // Need distinct restricted commons X (A-C) and Y (B-C); Z is neither.
+                for x in mask_to_vec(rcc_a) {
+                    for y in mask_to_vec(rcc_b).into_iter().filter(|&y| y != x) {
+                        let used = (1 << (x - 1)) | (1 << (y - 1));
+                        for z in mask_to_vec(a.candidates & b.candidates & !used) {
+                            collect_targets(
+                                &mut targets,
+                                ctx.common_buddies(&[a, b], z, exclude),
+                                z,
+                            );
+                        }
+                    }
+                }
+                if targets.is_empty() {
+                    continue;
+                }
+                let step =
+                    construct_als_step(Technique::AlsXyWing, board, &[a, b, c], None, targets);
+                if accepts(&step) {
+                    return Some(step);
+                }
+            }
+        }
+    }
+    None
+}
+
+// --- Death Blossom ---
+
+/// Largest stem cell searched by `find_death_blossom`.
+const MAX_STEM_CANDIDATES: u32 = 4;
+
+/// Searches for Death Blossom: a stem cell whose every candidate D is tied to a petal
+/// ALS where all positions of D see the stem. Whatever the stem holds, one petal loses
+/// its D and is locked, so a digit Z in every petal is eliminated from cells seeing all
+/// of its positions.
+pub fn find_death_blossom(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let ctx = AlsContext::new(board);
+
+    for stem in 0..81 {
+        let stem_count = board.candidates[stem].count_ones();
+        if board.cells[stem] != 0 || !(2..=MAX_STEM_CANDIDATES).contains(&stem_count) {
+            continue;
+        }
+        let stem_bit = 1u128 << stem;
+        let digits = mask_to_vec(board.candidates[stem]);
+
+        let petals: Vec<Vec<&Als>> = digits
+            .iter()
+            .map(|&d| {
+                let i = (d - 1) as usize;
+                ctx.sets
+                    .iter()
+                    .filter(|als| {
+                        als.cells & stem_bit == 0
+                            && als.digit_cells[i] != 0
+                            && als.digit_cells[i] & !ctx.peer_masks[stem] == 0
+                            && als.candidates & !board.candidates[stem] != 0
+                    })
+                    .collect()
+            })
+            .collect();
+        if petals.iter().any(|p| p.is_empty()) {
+            continue;
+        }
+
+        let mut chosen = Vec::with_capacity(digits.len());
+        if let Some(step) = choose_petals(
+            board,
+            &ctx,
+            stem,
+            &petals,
+            &mut chosen,
+            stem_bit,
+            !board.candidates[stem] & 0x1FF,
+            accepts,
+        ) {
+            return Some(step);
+        }
+    }
+    None
+}
+
+/// Picks one disjoint petal per stem candidate, keeping track of the digits common to
+/// every petal chosen so far.
+#[allow(clippy::too_many_arguments)]
+fn choose_petals<'a>(
+    board: &LogicalBoard,
+    ctx: &AlsContext,
+    stem: usize,
+    petals: &[Vec<&'a Als>],
+    chosen: &mut Vec<&'a Als>,
+    used_cells: u128,
+    common: u16,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    if chosen.len() == petals.len() {
+        let mut targets = Vec::new();
+        for z in mask_to_vec(common) {
+            collect_targets(&mut targets, ctx.common_buddies(chosen, z, used_cells), z);
+        }
+        if targets.is_empty() {
+            return None;
+        }
+        return Some(construct_als_step(
+            Technique::DeathBlossom,
+            board,
+            chosen,
+            Some(stem),
+            targets,
+        ))
+        .filter(|step| accepts(step));
+    }
+
+    for &petal in &petals[chosen.len()] {
+        let next_common = common & petal.candidates;
+        if petal.cells & used_cells != 0 || next_common == 0 {
+            continue;
+        }
+        chosen.push(petal);
+        if let Some(step) = choose_petals(
+            board,
+            ctx,
+            stem,
+            petals,
+            chosen,
+            used_cells | petal.cells,
+            next_common,
+            accepts,
+        ) {
+            return Some(step);
+        }
+        chosen.pop();
+    }
+    None
+}
+
+// --- Helpers ---
+
+/// Adds an elimination of `value` for every cell in the bitset, skipping duplicates.
+fn collect_targets(targets: &mut Vec<Elimination>, cells: u128, value: u8) {
+    for idx in (0..81).filter(|&idx| cells & (1 << idx) != 0) {
+        if !targets.iter().any(|e| e.index == idx && e.value == value) {
+            targets.push(Elimination { index: idx, value });
+        }
+    }
+}
+
+/// Builds the step, reporting each set's cells with its number as role and the
+/// Death Blossom stem, if any, first.
+fn construct_als_step(
+    technique: Technique,
+    board: &LogicalBoard,
+    sets: &[&Als],
+    stem: Option<usize>,
+    eliminations: Vec<Elimination>,
+) -> SolvingStep {
+    let mut cause: Vec<CauseCell> = stem
+        .into_iter()
+        .map(|idx| CauseCell {
+            index: idx,
+            candidates: mask_to_vec(board.candidates[idx]),
+            role: None,
+        })
+        .collect();
+    for (k, als) in sets.iter().enumerate() {
+        cause.extend(als.indices.iter().map(|&idx| CauseCell {
+            index: idx,
+            candidates: mask_to_vec(board.candidates[idx]),
+            role: Some(CauseRole::Als(k as u8)),
+        }));
+    }
+
+    SolvingStep {
+        technique,
+        placements: vec![],
+        eliminations,
+        cause,
+    }
+}
@@ -17,63 +17,67 @@
 */
 
 use super::{ALL_UNITS, LogicalBoard, PEER_MAP, mask_to_vec};
-use crate::types::{Elimination, Placement, SolvingStep};
+use crate::types::{Elimination, Placement, SolvingStep, Technique};
 
 /// Searches for a cell with exactly one candidate.
-pub fn find_naked_single(board: &LogicalBoard) -> Option<SolvingStep> {
+pub fn find_naked_single(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     for i in 0..81 {
         if board.cells[i] == 0 && board.candidates[i].count_ones() == 1 {
             let value = (board.candidates[i].trailing_zeros() + 1) as u8;
             let eliminations = collect_peer_eliminations(board, i, value);
 
-            return Some(SolvingStep {
-                technique: "NakedSingle".to_string(),
+            let step = SolvingStep {
+                technique: Technique::NakedSingle,
                 placements: vec![Placement { index: i, value }],
                 eliminations,
                 cause: vec![],
-            });
+            };
+            if accepts(&step) {
+                return Some(step);
+            }
         }
     }
     None
 }
 
 /// Searches for a candidate that appears only once in a specific group (row/col/box).
-pub fn find_hidden_single(board: &LogicalBoard) -> Option<SolvingStep> {
+pub fn find_hidden_single(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     let all_units: &[&[usize]] = &ALL_UNITS;
     for unit in all_units.iter() {
-        if let Some(step) = find_hidden_single_in_group(board, unit) {
+        if let Some(step) = find_hidden_single_in_group(board, unit, accepts) {
             return Some(step);
         }
     }
     None
 }
 
-fn find_hidden_single_in_group(board: &LogicalBoard, group: &[usize]) -> Option<SolvingStep> {
+fn find_hidden_single_in_group(
+    board: &LogicalBoard,
+    group: &[usize],
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     for num in 1..=9 {
         if let Some(target_idx) = find_unique_position_in_group(board, group, num) {
-            let mask = 1 << (num - 1);
-            let mut eliminations = collect_peer_eliminations(board, target_idx, num);
-
-            // Internal eliminations: remove other candidates from the target cell
-            let other_cands = board.candidates[target_idx] & !mask;
-            if other_cands != 0 {
-                for cand in mask_to_vec(other_cands) {
-                    eliminations.push(Elimination {
-                        index: target_idx,
-                        value: cand,
-                    });
-                }
-            }
+            let eliminations = collect_placement_eliminations(board, target_idx, num);
 
-            return Some(SolvingStep {
-                technique: "HiddenSingle".to_string(),
+            let step = SolvingStep {
+                technique: Technique::HiddenSingle,
                 placements: vec![Placement {
                     index: target_idx,
                     value: num,
                 }],
                 eliminations,
                 cause: vec![],
-            });
+            };
+            if accepts(&step) {
+                return Some(step);
+            }
         }
     }
     None
@@ -94,6 +98,25 @@ fn collect_peer_eliminations(board: &LogicalBoard, index: usize, value: u8) -> V
         .collect()
 }
 
+/// Helper to collect every elimination caused by placing `value` in a cell:
+/// the value from its peers, and the other candidates from the cell itself.
+pub(super) fn collect_placement_eliminations(
+    board: &LogicalBoard,
+    index: usize,
+    value: u8,
+) -> Vec<Elimination> {
+    let mut eliminations = collect_peer_eliminations(board, index, value);
+
+    // Internal eliminations: remove other candidates from the target cell
+    let other_cands = board.candidates[index] & !(1 << (value - 1));
+    if other_cands != 0 {
+        for cand in mask_to_vec(other_cands) {
+            eliminations.push(Elimination { index, value: cand });
+        }
+    }
+    eliminations
+}
+
 /// Helper to find the single index in a group where 'num' is a candidate.
 #[inline]
 fn find_unique_position_in_group(board: &LogicalBoard, group: &[usize], num: u8) -> Option<usize> {
@@ -0,0 +1,362 @@
+/*
+* Copyright (C) 2025-2026  Henrique Almeida
+* This file is part of WASudoku.
+*
+* WASudoku is free software: you can redistribute it and/or modify
+* it under the terms of the GNU Affero General Public License as published
+* by the Free Software Foundation, either version 3 of the License, or
+* (at your option) any later version.
+*
+* WASudoku is distributed in the hope that it will be useful,
+* but WITHOUT ANY WARRANTY; without even the implied warranty of
+* MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+* GNU Affero General Public License for more details.
+*
+* You should have received a copy of the GNU Affero General Public License
+* along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use super::wings::{get_bivalue_cells, get_common_peer_eliminations};
+use super::{ALL_UNITS, LogicalBoard, PEER_MAP, mask_to_vec};
+use crate::types::{CauseCell, SolvingStep, Technique};
+use std::collections::VecDeque;
+
+// --- X-Chain ---
+
+/// Searches for the shortest single-digit X-Chain.
+/// A chain of alternating strong and weak links on one digit, starting and ending
+/// with a strong link, means one of its two ends must hold the digit.
+pub fn find_x_chain(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let mut best: Option<SolvingStep> = None;
+
+    for num in 1..=9 {
+        let strong_links = get_strong_links(board, num);
+        let mask = 1 << (num - 1);
+
+        for start in 0..81 {
+            if strong_links[start].is_empty() || (board.candidates[start] & mask) == 0 {
+                continue;
+            }
+            if let Some(step) = search_x_chain_from(board, num, start, &strong_links, accepts) {
+                keep_shortest(&mut best, step);
+            }
+        }
+    }
+    best
+}
+
+/// Builds the conjugate pair graph for a digit.
+/// Two cells are strongly linked if they are the only two positions for `num` in a unit.
+pub(super) fn get_strong_links(board: &LogicalBoard, num: u8) -> Vec<Vec<usize>> {
+    let mask = 1 << (num - 1);
+    let mut links = vec![Vec::new(); 81];
+
+    for unit in ALL_UNITS.iter() {
+        let positions: Vec<usize> = unit
+            .iter()
+            .filter(|&&idx| board.cells[idx] == 0 && (board.candidates[idx] & mask) != 0)
+            .cloned()
+            .collect();
+
+        if positions.len() == 2 {
+            let (a, b) = (positions[0], positions[1]);
+            if !links[a].contains(&b) {
+                links[a].push(b);
+                links[b].push(a);
+            }
+        }
+    }
+    links
+}
+
+/// Breadth-first search for the shortest productive X-Chain starting at `start`.
+/// The start is assumed OFF; strong links turn the next cell ON, weak links turn it OFF.
+/// States are `cell * 2 + on`, so a cell can be reached once OFF and once ON.
+fn search_x_chain_from(
+    board: &LogicalBoard,
+    num: u8,
+    start: usize,
+    strong_links: &[Vec<usize>],
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let mask = 1 << (num - 1);
+    let mut parent: [Option<usize>; 162] = [None; 162];
+    let mut visited = [false; 162];
+    let mut queue = VecDeque::new();
+
+    visited[start * 2] = true;
+    queue.push_back((start, false, 1));
+
+    while let Some((cell, is_on, len)) = queue.pop_front() {
+        let state = cell * 2 + is_on as usize;
+        if is_on {
+            // Need at least 3 links; a single conjugate pair is handled by simpler techniques.
+            // Returning to the start would be a loop, not a chain.
+            if len >= 4 && cell != start {
+                let elims = get_common_peer_eliminations(board, start, cell, num);
+                if !elims.is_empty() {
+                    let chain: Vec<usize> = reconstruct_chain(&parent, start * 2, state)
+                        .into_iter()
+                        .map(|s| s / 2)
+                        .collect();
+                    let step = SolvingStep {
+                        technique: Technique::XChain,
+                        placements: vec![],
+                        eliminations: elims,
+                        cause: chain
+                            .iter()
+                            .map(|&idx| CauseCell {
+                                index: idx,
+                                candidates: vec![num],
+                                role: None,
+                            })
+                            .collect(),
+                    };
+                    if accepts(&step) {
+                        return Some(step);
+                    }
+                }
+            }
+
+            // Weak link: any peer holding the digit is turned OFF.
+            for &peer in &PEER_MAP[cell] {
+                if !visited[peer * 2]
+                    && board.cells[peer] == 0
+                    && (board.candidates[peer] & mask) != 0
+                {
+                    visited[peer * 2] = true;
+                    parent[peer * 2] = Some(state);
+                    queue.push_back((peer, false, len + 1));
+                }
+            }
+        } else {
+            // Strong link: the conjugate partner is turned ON.
+            for &next in &strong_links[cell] {
+                if !visited[next * 2 + 1] {
+                    visited[next * 2 + 1] = true;
+                    parent[next * 2 + 1] = Some(state);
+                    queue.push_back((next, true, len + 1));
+                }
+            }
+        }
+    }
+    None
+}
+
+// --- XY-Chain ---
+
+/// Searches for the shortest XY-Chain.
+/// A chain of bivalue cells where each cell is linked to the next by a shared digit.
+/// If the first cell is not X, the last cell must be X, so X is eliminated from
+/// every cell seeing both ends.
+pub fn find_xy_chain(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let bivalue_cells = get_bivalue_cells(board);
+    if bivalue_cells.len() < 3 {
+        return None;
+    }
+
+    let mut is_bivalue = [false; 81];
+    for &(idx, _) in &bivalue_cells {
+        is_bivalue[idx] = true;
+    }
+
+    let mut best: Option<SolvingStep> = None;
+    for &(start, mask) in &bivalue_cells {
+        for num in mask_to_vec(mask) {
+            if let Some(step) = search_xy_chain_from(board, start, num, &is_bivalue, accepts) {
+                keep_shortest(&mut best, step);
+            }
+        }
+    }
+    best
+}
+
+/// Breadth-first search for the shortest productive XY-Chain starting at `start`
+/// with `num` assumed false in the first cell. States are `cell * 9 + value - 1` for
+/// the value a cell is left with, so a cell can be reached once with each value.
+fn search_xy_chain_from(
+    board: &LogicalBoard,
+    start: usize,
+    num: u8,
+    is_bivalue: &[bool; 81],
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let state_of = |cell: usize, value: u8| cell * 9 + value as usize - 1;
+    let mut parent: Vec<Option<usize>> = vec![None; 81 * 9];
+    let mut visited = vec![false; 81 * 9];
+    let mut queue = VecDeque::new();
+
+    let start_value = other_candidate(board.candidates[start], num);
+    visited[state_of(start, start_value)] = true;
+    queue.push_back((start, start_value, 1));
+
+    while let Some((cell, on_value, len)) = queue.pop_front() {
+        let on_mask = 1 << (on_value - 1);
+        let state = state_of(cell, on_value);
+
+        for &peer in &PEER_MAP[cell] {
+            if !is_bivalue[peer] || (board.candidates[peer] & on_mask) == 0 {
+                continue;
+            }
+            let next_value = other_candidate(board.candidates[peer], on_value);
+            let next_state = state_of(peer, next_value);
+            if visited[next_state] {
+                continue;
+            }
+            visited[next_state] = true;
+            parent[next_state] = Some(state);
+
+            if next_value == num && len + 1 >= 3 && peer != start {
+                let elims = get_common_peer_eliminations(board, start, peer, num);
+                if !elims.is_empty() {
+                    let chain: Vec<usize> =
+                        reconstruct_chain(&parent, state_of(start, start_value), next_state)
+                            .into_iter()
+                            .map(|s| s / 9)
+                            .collect();
+                    let step = SolvingStep {
+                        technique: Technique::XYChain,
+                        placements: vec![],
+                        eliminations: elims,
+                        cause: chain
+                            .iter()
+                            .map(|&idx| CauseCell {
+                                index: idx,
+                                candidates: mask_to_vec(board.candidates[idx]),
+                                role: None,
+                            })
+                            .collect(),
+                    };
+                    if accepts(&step) {
+                        return Some(step);
+                    }
+                }
+            }
+            queue.push_back((peer, next_value, len + 1));
+        }
+    }
+    None
+}
+
+// --- Remote Pairs ---
+
+/// Searches for the shortest Remote Pair.
+/// A chain of bivalue cells holding the same two digits alternates between them, so
+/// two ends an odd number of links apart hold both digits between them. Both digits
+/// are eliminated from every cell seeing those two ends.
+pub fn find_remote_pairs(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let bivalue_cells = get_bivalue_cells(board);
+    let mut best: Option<SolvingStep> = None;
+
+    for &(start, mask) in &bivalue_cells {
+        let same_pair: Vec<usize> = bivalue_cells
+            .iter()
+            .filter(|&&(_, m)| m == mask)
+            .map(|&(idx, _)| idx)
+            .collect();
+        // A productive chain needs at least 4 cells.
+        if same_pair.len() < 4 {
+            continue;
+        }
+        if let Some(step) = search_remote_pair_from(board, start, mask, &same_pair, accepts) {
+            keep_shortest(&mut best, step);
+        }
+    }
+    best
+}
+
+/// Breadth-first search over same-pair peers for the shortest productive Remote Pair.
+fn search_remote_pair_from(
+    board: &LogicalBoard,
+    start: usize,
+    mask: u16,
+    same_pair: &[usize],
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let mut parent: [Option<usize>; 81] = [None; 81];
+    let mut visited = [false; 81];
+    let mut queue = VecDeque::new();
+
+    visited[start] = true;
+    queue.push_back((start, 0));
+
+    while let Some((cell, links)) = queue.pop_front() {
+        // An odd number of links (3 or more) puts opposite digits on the two ends.
+        if links >= 3 && links % 2 == 1 {
+            let chain = reconstruct_chain(&parent, start, cell);
+            let mut elims = Vec::new();
+            for value in mask_to_vec(mask) {
+                elims.extend(
+                    get_common_peer_eliminations(board, start, cell, value)
+                        .into_iter()
+                        .filter(|e| !chain.contains(&e.index)),
+                );
+            }
+            if !elims.is_empty() {
+                let step = SolvingStep {
+                    technique: Technique::RemotePair,
+                    placements: vec![],
+                    eliminations: elims,
+                    cause: chain
+                        .iter()
+                        .map(|&idx| CauseCell {
+                            index: idx,
+                            candidates: mask_to_vec(mask),
+                            role: None,
+                        })
+                        .collect(),
+                };
+                if accepts(&step) {
+                    return Some(step);
+                }
+            }
+        }
+
+        for &next in same_pair {
+            if !visited[next] && PEER_MAP[cell].contains(&next) {
+                visited[next] = true;
+                parent[next] = Some(cell);
+                queue.push_back((next, links + 1));
+            }
+        }
+    }
+    None
+}
+
+/// Returns the candidate of a bivalue cell that is not `value`.
+#[inline]
+fn other_candidate(mask: u16, value: u8) -> u8 {
+    ((mask & !(1 << (value - 1))).trailing_zeros() + 1) as u8
+}
+
+/// Walks parent links back from `end` to `start`, returning the chain in order.
+fn reconstruct_chain(parent: &[Option<usize>], start: usize, end: usize) -> Vec<usize> {
+    let mut chain = vec![end];
+    let mut current = end;
+    while current != start {
+        current = parent[current].expect("chain cells always have a parent");
+        chain.push(current);
+    }
+    chain.reverse();
+    chain
+}
+
+/// Keeps whichever step has the shorter chain, so the simplest explanation wins.
+#[inline]
+fn keep_shortest(best: &mut Option<SolvingStep>, step: SolvingStep) {
+    if best
+        .as_ref()
+        .is_none_or(|current| step.cause.len() < current.cause.len())
+    {
+        *best = Some(step);
+    }
+}
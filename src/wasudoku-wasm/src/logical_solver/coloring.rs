@@ -0,0 +1,308 @@
+/*
+* Copyright (C) 2025-2026  Henrique Almeida
+* This file is part of WASudoku.
+*
+* WASudoku is free software: you can redistribute it and/or modify
+* it under the terms of the GNU Affero General Public License as published
+* by the Free Software Foundation, either version 3 of the License, or
+* (at your option) any later version.
+*
+* WASudoku is distributed in the hope that it will be useful,
+* but WITHOUT ANY WARRANTY; without even the implied warranty of
+* MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+* GNU Affero General Public License for more details.
+*
+* You should have received a copy of the GNU Affero General Public License
+* along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use super::wings::are_peers;
+use super::{BOX_UNITS, LogicalBoard};
+use crate::types::{CauseCell, CauseRole, Elimination, SolvingStep, Technique};
+
+/// A connected component of the conjugate pair graph of a digit, split into its
+/// two colors. Exactly one of the colors holds the digit.
+struct ColorComponent {
+    colors: [Vec<usize>; 2],
+    /// Bitset of every cell in the component.
+    cells: u128,
+}
+
+// --- Simple Coloring ---
+
+/// Searches for Simple Coloring eliminations on a single digit.
+/// Color Wrap: two cells of the same color see each other, so that color is false.
+/// Color Trap: a cell seeing both colors of a component cannot hold the digit.
+pub fn find_simple_coloring(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let (row_masks, col_masks) = board.get_all_fish_masks();
+
+    for num in 1..=9 {
+        let components = build_color_components(board, num, &row_masks[num], &col_masks[num]);
+        for comp in &components {
+            if let Some(step) = check_color_wrap(num, comp, accepts) {
+                return Some(step);
+            }
+            if let Some(step) = check_color_trap(board, num, comp)
+                && accepts(&step)
+            {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+fn check_color_wrap(
+    num: usize,
+    comp: &ColorComponent,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    for color in &comp.colors {
+        let clashes = color
+            .iter()
+            .enumerate()
+            .any(|(i, &a)| color[i + 1..].iter().any(|&b| are_peers(a, b)));
+        if clashes {
+            let step =
+                construct_coloring_step(Technique::SimpleColoringWrap, num, color.clone(), &[comp]);
+            if accepts(&step) {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+fn check_color_trap(
+    board: &LogicalBoard,
+    num: usize,
+    comp: &ColorComponent,
+) -> Option<SolvingStep> {
+    let targets = trapped_cells(board, num, comp.cells, &comp.colors[0], &comp.colors[1]);
+    if targets.is_empty() {
+        return None;
+    }
+    Some(construct_coloring_step(
+        Technique::SimpleColoringTrap,
+        num,
+        targets,
+        &[comp],
+    ))
+}
+
+// --- Multi-Coloring ---
+
+/// Searches for Multi-Coloring eliminations between two components of one digit.
+/// If color A1 sees color B1, at most one of them is true, so at least one of their
+/// opposites A2 and B2 is. Cells seeing both A2 and B2 lose the digit, and a color that
+/// sees both colors of the other component is false.
+pub fn find_multi_coloring(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let (row_masks, col_masks) = board.get_all_fish_masks();
+
+    for num in 1..=9 {
+        let components = build_color_components(board, num, &row_masks[num], &col_masks[num]);
+        for (i, comp_a) in components.iter().enumerate() {
+            for comp_b in &components[i + 1..] {
+                if let Some(step) = check_multi_coloring_pair(board, num, comp_a, comp_b, accepts) {
+                    return Some(step);
+                }
+            }
+        }
+    }
+    None
+}
+
+fn check_multi_coloring_pair(
+    board: &LogicalBoard,
+    num: usize,
+    comp_a: &ColorComponent,
+    comp_b: &ColorComponent,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    // linked(i, j): some cell of color `i` of A sees some cell of color `j` of B.
+    let linked = |i: usize, j: usize| {
+        comp_a.colors[i]
+            .iter()
+            .any(|&a| comp_b.colors[j].iter().any(|&b| are_peers(a, b)))
+    };
+
+    // Wrap: a color seeing both colors of the other component is false.
+    for i in 0..2 {
+        if linked(i, 0) && linked(i, 1) {
+            let targets = comp_a.colors[i].clone();
+            let step =
+                construct_coloring_step(Technique::MultiColoring, num, targets, &[comp_a, comp_b]);
+            if accepts(&step) {
+                return Some(step);
+            }
+        }
+        if linked(0, i) && linked(1, i) {
+            let targets = comp_b.colors[i].clone();
+            let step =
+                construct_coloring_step(Technique::MultiColoring, num, targets, &[comp_a, comp_b]);
+            if accepts(&step) {
+                return Some(step);
+            }
+        }
+    }
+
+    // Trap: cells seeing both opposites of a linked color pair.
+    let colored = comp_a.cells | comp_b.cells;
+    for i in 0..2 {
+        for j in 0..2 {
+            if !linked(i, j) {
+                continue;
+            }
+            let targets = trapped_cells(
+                board,
+                num,
+                colored,
+                &comp_a.colors[1 - i],
+                &comp_b.colors[1 - j],
+            );
+            if !targets.is_empty() {
+                let step = construct_coloring_step(
+                    Technique::MultiColoring,
+                    num,
+                    targets,
+                    &[comp_a, comp_b],
+                );
+                if accepts(&step) {
+                    return Some(step);
+                }
+            }
+        }
+    }
+    None
+}
+
+// --- Helpers ---
+
+/// Builds the conjugate pair graph of a digit from its row, column and box positions and
+/// two-colors each connected component.
+fn build_color_components(
+    board: &LogicalBoard,
+    num: usize,
+    row_masks: &[u16; 9],
+    col_masks: &[u16; 9],
+) -> Vec<ColorComponent> {
+    let mut links: Vec<Vec<usize>> = vec![Vec::new(); 81];
+    let mut add_link = |a: usize, b: usize| {
+        if !links[a].contains(&b) {
+            links[a].push(b);
+            links[b].push(a);
+        }
+    };
+
+    for i in 0..9 {
+        if row_masks[i].count_ones() == 2 {
+            let cols = mask_positions(row_masks[i]);
+            add_link(i * 9 + cols[0], i * 9 + cols[1]);
+        }
+        if col_masks[i].count_ones() == 2 {
+            let rows = mask_positions(col_masks[i]);
+            add_link(rows[0] * 9 + i, rows[1] * 9 + i);
+        }
+    }
+    let cand_bit = 1 << (num - 1);
+    for box_unit in BOX_UNITS.iter() {
+        let box_cells: Vec<usize> = box_unit
+            .iter()
+            .copied()
+            .filter(|&idx| board.cells[idx] == 0 && (board.candidates[idx] & cand_bit) != 0)
+            .collect();
+        if box_cells.len() == 2 {
+            add_link(box_cells[0], box_cells[1]);
+        }
+    }
+
+    let mut visited = [false; 81];
+    let mut components = Vec::new();
+    for start in 0..81 {
+        if visited[start] || links[start].is_empty() {
+            continue;
+        }
+        let mut colors: [Vec<usize>; 2] = [Vec::new(), Vec::new()];
+        let mut cells = 0u128;
+        let mut stack = vec![(start, 0)];
+        visited[start] = true;
+        while let Some((cell, color)) = stack.pop() {
+            colors[color].push(cell);
+            cells |= 1 << cell;
+            for &next in &links[cell] {
+                if !visited[next] {
+                    visited[next] = true;
+                    stack.push((next, 1 - color));
+                }
+            }
+        }
+        colors.iter_mut().for_each(|c| c.sort_unstable());
+        components.push(ColorComponent { colors, cells });
+    }
+    components
+}
+
+/// Returns the uncolored cells holding the digit that see a cell of both `color_a`
+/// and `color_b`.
+fn trapped_cells(
+    board: &LogicalBoard,
+    num: usize,
+    colored: u128,
+    color_a: &[usize],
+    color_b: &[usize],
+) -> Vec<usize> {
+    let cand_bit = 1 << (num - 1);
+    (0..81)
+        .filter(|&idx| {
+            board.cells[idx] == 0
+                && (board.candidates[idx] & cand_bit) != 0
+                && (colored & (1 << idx)) == 0
+                && color_a.iter().any(|&c| are_peers(idx, c))
+                && color_b.iter().any(|&c| are_peers(idx, c))
+        })
+        .collect()
+}
+
+/// Builds a coloring step. Each component contributes two colors, numbered in
+/// order, so the UI can paint every color of the cause.
+fn construct_coloring_step(
+    technique: Technique,
+    num: usize,
+    targets: Vec<usize>,
+    components: &[&ColorComponent],
+) -> SolvingStep {
+    let mut cause = Vec::new();
+    for (c, comp) in components.iter().enumerate() {
+        for (k, color) in comp.colors.iter().enumerate() {
+            cause.extend(color.iter().map(|&idx| CauseCell {
+                index: idx,
+                candidates: vec![num as u8],
+                role: Some(CauseRole::Color((c * 2 + k) as u8)),
+            }));
+        }
+    }
+
+    SolvingStep {
+        technique,
+        placements: vec![],
+        eliminations: targets
+            .into_iter()
+            .map(|idx| Elimination {
+                index: idx,
+                value: num as u8,
+            })
+            .collect(),
+        cause,
+    }
+}
+
+#[inline]
+fn mask_positions(mask: u16) -> Vec<usize> {
+    (0..9).filter(|&i| (mask & (1 << i)) != 0).collect()
+}
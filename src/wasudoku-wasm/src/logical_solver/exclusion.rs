@@ -0,0 +1,209 @@
+/*
+* Copyright (C) 2025-2026  Henrique Almeida
+* This file is part of WASudoku.
+*
+* WASudoku is free software: you can redistribute it and/or modify
+* it under the terms of the GNU Affero General Public License as published
+* by the Free Software Foundation, either version 3 of the License, or
+* (at your option) any later version.
+*
+* WASudoku is distributed in the hope that it will be useful,
+* but WITHOUT ANY WARRANTY; without even the implied warranty of
+* MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+* GNU Affero General Public License for more details.
+*
+* You should have received a copy of the GNU Affero General Public License
+* along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use super::wings::are_peers;
+use super::{LogicalBoard, mask_to_vec};
+use crate::types::{CauseCell, Elimination, SolvingStep, Technique};
+
+/// A cell outside the aligned set that could be emptied by a combination of values.
+struct ExclusionCell {
+    index: usize,
+    /// Bitmask of the positions in the aligned set of the cells this one sees.
+    seen: u8,
+}
+
+// --- Aligned Pair Exclusion ---
+
+/// Searches for Aligned Pair Exclusion: every combination of values for two cells that
+/// see each other or share a bivalue peer is enumerated, and the ones that would leave
+/// a bivalue peer of both without candidates (or repeat a value in cells that see each
+/// other) are rejected. A candidate that appears in no surviving combination is
+/// eliminated.
+pub fn find_aligned_pair_exclusion(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let cells = get_unsolved_cells(board);
+    let bivalue_cells = get_small_cells(board, 2);
+    for (i, &a) in cells.iter().enumerate() {
+        let aligned = |b: usize| {
+            are_peers(a, b)
+                || bivalue_cells
+                    .iter()
+                    .any(|&c| are_peers(c, a) && are_peers(c, b))
+        };
+        for &b in cells[i + 1..].iter().filter(|&&b| aligned(b)) {
+            if let Some(step) = check_aligned_exclusion(
+                board,
+                &[a, b],
+                &bivalue_cells,
+                Technique::AlignedPairExclusion,
+            ) && accepts(&step)
+            {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+// --- Aligned Triple Exclusion ---
+
+/// Searches for Aligned Triple Exclusion, the same reasoning applied to three cells.
+/// Each cell must see at least one other cell of the triple.
+pub fn find_aligned_triple_exclusion(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let cells = get_unsolved_cells(board);
+    let small_cells = get_small_cells(board, 3);
+    for (i, &a) in cells.iter().enumerate() {
+        for (j, &b) in cells.iter().enumerate().skip(i + 1) {
+            for &c in &cells[j + 1..] {
+                let aligned = (are_peers(a, b) || are_peers(a, c))
+                    && (are_peers(b, a) || are_peers(b, c))
+                    && (are_peers(c, a) || are_peers(c, b));
+                if !aligned {
+                    continue;
+                }
+                if let Some(step) = check_aligned_exclusion(
+                    board,
+                    &[a, b, c],
+                    &small_cells,
+                    Technique::AlignedTripleExclusion,
+                ) && accepts(&step)
+                {
+                    return Some(step);
+                }
+            }
+        }
+    }
+    None
+}
+
+// --- Helpers ---
+
+fn get_unsolved_cells(board: &LogicalBoard) -> Vec<usize> {
+    (0..81).filter(|&idx| board.cells[idx] == 0).collect()
+}
+
+/// Unsolved cells with at most `max` candidates, the only ones a set of `max` cells
+/// can empty.
+fn get_small_cells(board: &LogicalBoard, max: u32) -> Vec<usize> {
+    (0..81)
+        .filter(|&idx| board.cells[idx] == 0 && board.candidates[idx].count_ones() <= max)
+        .collect()
+}
+
+/// Enumerates the value combinations of `set` and eliminates the candidates that are
+/// part of no valid combination. Only the `small_cells` that see enough of the set to
+/// be emptied by it can reject a combination.
+fn check_aligned_exclusion(
+    board: &LogicalBoard,
+    set: &[usize],
+    small_cells: &[usize],
+    technique: Technique,
+) -> Option<SolvingStep> {
+    let exclusion_cells: Vec<ExclusionCell> = small_cells
+        .iter()
+        .filter(|idx| !set.contains(idx))
+        .filter_map(|&idx| {
+            let (seen, reachable) = (0..set.len())
+                .filter(|&k| are_peers(idx, set[k]))
+                .fold((0u8, 0u16), |(seen, mask), k| {
+                    (seen | 1 << k, mask | board.candidates[set[k]])
+                });
+            let cands = board.candidates[idx];
+            (seen.count_ones() >= 2
+                && cands.count_ones() <= seen.count_ones()
+                && cands & !reachable == 0)
+                .then_some(ExclusionCell { index: idx, seen })
+        })
+        .collect();
+    if exclusion_cells.is_empty() {
+        return None;
+    }
+
+    let mut allowed = vec![0u16; set.len()];
+    let mut values = vec![0u8; set.len()];
+    enumerate_combinations(board, set, &exclusion_cells, &mut values, 0, &mut allowed);
+
+    let eliminations: Vec<Elimination> = set
+        .iter()
+        .zip(&allowed)
+        .flat_map(|(&idx, &mask)| {
+            mask_to_vec(board.candidates[idx] & !mask)
+                .into_iter()
+                .map(move |value| Elimination { index: idx, value })
+        })
+        .collect();
+    if eliminations.is_empty() {
+        return None;
+    }
+
+    let cause = set
+        .iter()
+        .chain(exclusion_cells.iter().map(|c| &c.index))
+        .map(|&idx| CauseCell {
+            index: idx,
+            candidates: mask_to_vec(board.candidates[idx]),
+            role: None,
+        })
+        .collect();
+
+    Some(SolvingStep {
+        technique,
+        placements: vec![],
+        eliminations,
+        cause,
+    })
+}
+
+/// Assigns a value to `set[pos]` and onwards, marking the values of every valid
+/// complete combination in `allowed`.
+fn enumerate_combinations(
+    board: &LogicalBoard,
+    set: &[usize],
+    exclusion_cells: &[ExclusionCell],
+    values: &mut [u8],
+    pos: usize,
+    allowed: &mut [u16],
+) {
+    if pos == set.len() {
+        let empties_a_cell = exclusion_cells.iter().any(|cell| {
+            let taken = (0..set.len())
+                .filter(|&k| cell.seen & (1 << k) != 0)
+                .fold(0u16, |mask, k| mask | 1 << (values[k] - 1));
+            board.candidates[cell.index] & !taken == 0
+        });
+        if !empties_a_cell {
+            for (mask, &value) in allowed.iter_mut().zip(values.iter()) {
+                *mask |= 1 << (value - 1);
+            }
+        }
+        return;
+    }
+
+    for value in mask_to_vec(board.candidates[set[pos]]) {
+        let clashes = (0..pos).any(|k| values[k] == value && are_peers(set[k], set[pos]));
+        if !clashes {
+            values[pos] = value;
+            enumerate_combinations(board, set, exclusion_cells, values, pos + 1, allowed);
+        }
+    }
+}
@@ -0,0 +1,206 @@
+/*
+* Copyright (C) 2025-2026  Henrique Almeida
+* This file is part of WASudoku.
+*
+* WASudoku is free software: you can redistribute it and/or modify
+* it under the terms of the GNU Affero General Public License as published
+* by the Free Software Foundation, either version 3 of the License, or
+* (at your option) any later version.
+*
+* WASudoku is distributed in the hope that it will be useful,
+* but WITHOUT ANY WARRANTY; without even the implied warranty of
+* MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+* GNU Affero General Public License for more details.
+*
+* You should have received a copy of the GNU Affero General Public License
+* along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use super::{LogicalBoard, mask_to_vec};
+use crate::types::{CauseCell, Elimination, SolvingStep, Technique};
+
+/// The cells of a Junior Exocet pattern, independent of the board contents.
+struct ExocetShape {
+    base: [usize; 2],
+    targets: [usize; 2],
+    /// The cells sharing a line with a target inside the band, in the other target's
+    /// cross-line. They must not hold a base digit.
+    companions: [usize; 2],
+    /// The cross-lines: the target lines and the free line of the base box, as cells
+    /// outside the band (the "S" cells), one `Vec` per line.
+    cross_lines: [Vec<usize>; 3],
+}
+
+lazy_static::lazy_static! {
+    /// Every Junior Exocet shape on the grid, along rows and along columns.
+    static ref EXOCET_SHAPES: Vec<ExocetShape> = {
+        let mut shapes = Vec::new();
+        for transposed in [false, true] {
+            // In the transposed shapes, "rows" are columns and "columns" are rows.
+            let cell = |r: usize, c: usize| if transposed { c * 9 + r } else { r * 9 + c };
+            let outside = |band: usize, c: usize| -> Vec<usize> {
+                (0..9).filter(|r| r / 3 != band).map(|r| cell(r, c)).collect()
+            };
+
+            for r0 in 0..9 {
+                let band = r0 / 3;
+                let r1 = band * 3 + (r0 + 1) % 3;
+                let r2 = band * 3 + (r0 + 2) % 3;
+                for cq in 0..9 {
+                    let stack = cq / 3;
+                    let base = [
+                        cell(r0, stack * 3 + (cq + 1) % 3),
+                        cell(r0, stack * 3 + (cq + 2) % 3),
+                    ];
+                    for ct1 in (0..9).filter(|c| c / 3 != stack) {
+                        for ct2 in (0..9).filter(|c| c / 3 != stack && c / 3 != ct1 / 3) {
+                            shapes.push(ExocetShape {
+                                base,
+                                targets: [cell(r1, ct1), cell(r2, ct2)],
+                                companions: [cell(r2, ct1), cell(r1, ct2)],
+                                cross_lines: [
+                                    outside(band, cq),
+                                    outside(band, ct1),
+                                    outside(band, ct2),
+                                ],
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        shapes
+    };
+}
+
+/// Searches for a Junior Exocet. Two base cells in a mini-line of a box hold three or
+/// four base digits, and two target cells sit in the other two boxes of the band, on
+/// the other two lines. If every base digit is confined to two lines within the cross-
+/// lines outside the band, and the companion cells hold no base digit, the two digits
+/// of the base cells must reappear in the targets. Non-base candidates are removed from
+/// the targets, and base digits neither target can hold are removed from the base.
+pub fn find_junior_exocet(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    EXOCET_SHAPES.iter().find_map(|shape| {
+        let base_mask = base_digits(board, shape.base);
+        if base_mask == 0 {
+            return None;
+        }
+        check_exocet(board, shape, base_mask).filter(|step| accepts(step))
+    })
+}
+
+/// The base digits of a pair of base cells: their combined candidates when both are
+/// unsolved and hold three or four of them, `0` otherwise.
+fn base_digits(board: &LogicalBoard, base: [usize; 2]) -> u16 {
+    if base.iter().any(|&idx| board.cells[idx] != 0) {
+        return 0;
+    }
+    let mask = board.candidates[base[0]] | board.candidates[base[1]];
+    if (3..=4).contains(&mask.count_ones()) {
+        mask
+    } else {
+        0
+    }
+}
+
+fn check_exocet(board: &LogicalBoard, shape: &ExocetShape, base_mask: u16) -> Option<SolvingStep> {
+    let holds_base_digit = |idx: usize| {
+        let value_bit = match board.cells[idx] {
+            0 => 0,
+            v => 1 << (v - 1),
+        };
+        (board.candidates[idx] | value_bit) & base_mask != 0
+    };
+
+    if shape
+        .targets
+        .iter()
+        .any(|&t| board.cells[t] != 0 || board.candidates[t] & base_mask == 0)
+    {
+        return None;
+    }
+    if shape.companions.iter().any(|&c| holds_base_digit(c)) {
+        return None;
+    }
+    if !mask_to_vec(base_mask)
+        .into_iter()
+        .all(|d| is_covered_by_two_lines(board, &shape.cross_lines, d))
+    {
+        return None;
+    }
+
+    let mut eliminations = Vec::new();
+    for &t in &shape.targets {
+        for value in mask_to_vec(board.candidates[t] & !base_mask) {
+            eliminations.push(Elimination { index: t, value });
+        }
+    }
+    let target_mask = board.candidates[shape.targets[0]] | board.candidates[shape.targets[1]];
+    for &b in &shape.base {
+        for value in mask_to_vec(board.candidates[b] & !target_mask) {
+            eliminations.push(Elimination { index: b, value });
+        }
+    }
+    if eliminations.is_empty() {
+        return None;
+    }
+
+    let cause = shape
+        .base
+        .iter()
+        .chain(&shape.targets)
+        .map(|&idx| CauseCell {
+            index: idx,
+            candidates: mask_to_vec(board.candidates[idx] & base_mask),
+            role: None,
+        })
+        .collect();
+
+    Some(SolvingStep {
+        technique: Technique::JuniorExocet,
+        placements: vec![],
+        eliminations,
+        cause,
+    })
+}
+
+/// Checks that the positions of `digit` in the cross-lines outside the band, placed or
+/// candidate, fit in two lines: two of the cross-lines, two of the perpendicular lines,
+/// or one of each. The digit can then occupy at most two of the three cross-lines there.
+fn is_covered_by_two_lines(board: &LogicalBoard, cross_lines: &[Vec<usize>; 3], digit: u8) -> bool {
+    let bit = 1 << (digit - 1);
+    // For each cross-line, the bitmask of the perpendicular lines (0..6) where it has the digit.
+    let positions: Vec<u8> = cross_lines
+        .iter()
+        .map(|line| {
+            line.iter().enumerate().fold(0u8, |mask, (k, &idx)| {
+                if board.cells[idx] == digit || board.candidates[idx] & bit != 0 {
+                    mask | 1 << k
+                } else {
+                    mask
+                }
+            })
+        })
+        .collect();
+
+    let used: Vec<usize> = (0..3).filter(|&l| positions[l] != 0).collect();
+    match used.len() {
+        0..=2 => true,
+        // Every cross-line has the digit: one cross-line plus one perpendicular line must
+        // cover everything, or two perpendicular lines must.
+        _ => {
+            let all = positions.iter().fold(0u8, |m, &p| m | p);
+            let two_perpendicular = all.count_ones() <= 2;
+            let cross_and_perpendicular = (0..3).any(|skip| {
+                let rest = (0..3)
+                    .filter(|&l| l != skip)
+                    .fold(0u8, |m, l| m | positions[l]);
+                rest.count_ones() <= 1
+            });
+            two_perpendicular || cross_and_perpendicular
+        }
+    }
+}
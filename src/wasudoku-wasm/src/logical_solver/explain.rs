@@ -0,0 +1,93 @@
+/*
+* Copyright (C) 2025-2026  Henrique Almeida
+* This file is part of WASudoku.
+*
+* WASudoku is free software: you can redistribute it and/or modify
+* it under the terms of the GNU Affero General Public License as published
+* by the Free Software Foundation, either version 3 of the License, or
+* (at your option) any later version.
+*
+* WASudoku is distributed in the hope that it will be useful,
+* but WITHOUT ANY WARRANTY; without even the implied warranty of
+* MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+* GNU Affero General Public License for more details.
+*
+* You should have received a copy of the GNU Affero General Public License
+* along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use super::forcing::find_contradiction_chain;
+use super::{LogicalBoard, PEER_MAP, PIPELINE, SolverConfig, TechniqueLevel};
+use crate::board::Board;
+use crate::solver;
+use crate::types::{CandidateExplanation, SolvingStep};
+
+/// Propagation depth of the chains built for an explanation. Every round of singles
+/// fills at least one cell, so singles run until they are exhausted.
+const MAX_EXPLANATION_DEPTH: usize = 81;
+
+/// Explains why `value` cannot go in `index`. The solution is checked first, so a
+/// correct digit is never reported as eliminated by the player's wrong marks elsewhere.
+/// Otherwise every instance of the default techniques is searched, from the simplest,
+/// for the first step that removes the candidate; failing that, the chain of singles
+/// from placing the candidate to a contradiction is returned. The techniques up to the
+/// Advanced level are searched first, so most candidates are explained without the
+/// slower Master searches.
+///
+/// ### Errors
+///
+/// Returns an `Err` if the index or value is out of range, the cell is solved, or
+/// `value` is not one of its candidates.
+pub fn explain_candidate(
+    board: &LogicalBoard,
+    index: usize,
+    value: u8,
+) -> Result<CandidateExplanation, String> {
+    if index >= 81 || !(1..=9).contains(&value) {
+        return Err(format!("Invalid candidate {} at index {}", value, index));
+    }
+    if board.cells[index] != 0 {
+        return Err(format!("The cell at index {} is already solved", index));
+    }
+    if board.candidates[index] & (1 << (value - 1)) == 0 {
+        return Err(format!("{} is not a candidate at index {}", value, index));
+    }
+
+    let mut solution = Board { cells: board.cells };
+    if solver::count_solutions(&solution) == 1
+        && solver::solve(&mut solution)
+        && solution.cells[index] == value
+    {
+        return Ok(CandidateExplanation::Correct);
+    }
+
+    let find_step = |config: &SolverConfig| {
+        let removes = |step: &SolvingStep| {
+            config.allows(step.technique) && removes_candidate(step, index, value)
+        };
+        config
+            .stages()
+            .into_iter()
+            .find_map(|i| PIPELINE[i].0(board, &removes))
+    };
+
+    let step = find_step(&SolverConfig::up_to(TechniqueLevel::Advanced))
+        .or_else(|| find_step(&SolverConfig::default()))
+        .or_else(|| find_contradiction_chain(board, index, value, MAX_EXPLANATION_DEPTH));
+    Ok(step.map_or(
+        CandidateExplanation::Unexplained,
+        CandidateExplanation::Eliminated,
+    ))
+}
+
+/// Whether the step eliminates the candidate, fills its cell with another digit, or
+/// places the digit in a peer.
+fn removes_candidate(step: &SolvingStep, index: usize, value: u8) -> bool {
+    step.eliminations
+        .iter()
+        .any(|e| e.index == index && e.value == value)
+        || step.placements.iter().any(|p| {
+            (p.index == index && p.value != value)
+                || (p.value == value && PEER_MAP[index].contains(&p.index))
+        })
+}
@@ -16,8 +16,60 @@
 * along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use super::LogicalBoard;
-use crate::types::{CauseCell, Elimination, SolvingStep};
+use super::forcing::Branch;
+use super::{ALL_UNITS, LogicalBoard, get_box_index};
+use crate::types::{CauseCell, CauseRole, Elimination, SolvingStep, Technique};
+
+/// A fish size and the techniques named after it.
+struct FishSize {
+    size: usize,
+    basic: Technique,
+    finned: Technique,
+    sashimi: Technique,
+    kraken: Technique,
+    franken: Technique,
+    mutant: Technique,
+}
+
+const FISH_SIZES: [FishSize; 3] = [
+    FishSize {
+        size: 2,
+        basic: Technique::XWing,
+        finned: Technique::FinnedXWing,
+        sashimi: Technique::SashimiXWing,
+        kraken: Technique::KrakenXWing,
+        franken: Technique::FrankenXWing,
+        mutant: Technique::MutantXWing,
+    },
+    FishSize {
+        size: 3,
+        basic: Technique::Swordfish,
+        finned: Technique::FinnedSwordfish,
+        sashimi: Technique::SashimiSwordfish,
+        kraken: Technique::KrakenSwordfish,
+        franken: Technique::FrankenSwordfish,
+        mutant: Technique::MutantSwordfish,
+    },
+    FishSize {
+        size: 4,
+        basic: Technique::Jellyfish,
+        finned: Technique::FinnedJellyfish,
+        sashimi: Technique::SashimiJellyfish,
+        kraken: Technique::KrakenJellyfish,
+        franken: Technique::FrankenJellyfish,
+        mutant: Technique::MutantJellyfish,
+    },
+];
+
+/// Which family of fish a search looks for.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FishKind {
+    Basic,
+    /// Finned/Sashimi fish, whose fins share a box.
+    Finned,
+    /// Kraken fish, whose fins each reach the elimination through a chain.
+    Kraken,
+}
 
 struct FishSearchContext<'a> {
     num: u8,
@@ -25,22 +77,53 @@ struct FishSearchContext<'a> {
     masks: &'a [u16; 9],
     size: usize,
     is_row_base: bool,
-    tech_name: &'a str,
+    names: &'a FishSize,
+    kind: FishKind,
+    accepts: &'a dyn Fn(&SolvingStep) -> bool,
 }
 
-pub fn find_fish_techniques(board: &LogicalBoard) -> Option<SolvingStep> {
+pub fn find_fish_techniques(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     // Calculate masks once for all Fish.
     // Returns row_masks[num][row] and col_masks[num][col]
     let (row_masks, col_masks) = board.get_all_fish_masks();
 
-    const FISH_CONFIGS: [(usize, &str); 3] = [(2, "X-Wing"), (3, "Swordfish"), (4, "Jellyfish")];
-
     for num in 1..=9 {
-        for &(size, name) in &FISH_CONFIGS {
-            if let Some(step) = check_fish(board, num, &row_masks[num], size, true, name) {
+        for names in &FISH_SIZES {
+            let kind = FishKind::Basic;
+            if let Some(step) = check_fish(board, num, &row_masks[num], true, names, kind, accepts)
+            {
+                return Some(step);
+            }
+            if let Some(step) = check_fish(board, num, &col_masks[num], false, names, kind, accepts)
+            {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+/// Searches for Finned and Sashimi fish, smallest first.
+/// Base candidates outside the cover lines (fins) must all lie in one box; the fish then
+/// only eliminates from cover cells inside that box, since they see every fin.
+pub fn find_finned_fish(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let (row_masks, col_masks) = board.get_all_fish_masks();
+
+    for names in &FISH_SIZES {
+        for num in 1..=9 {
+            let kind = FishKind::Finned;
+            if let Some(step) = check_fish(board, num, &row_masks[num], true, names, kind, accepts)
+            {
                 return Some(step);
             }
-            if let Some(step) = check_fish(board, num, &col_masks[num], size, false, name) {
+            if let Some(step) = check_fish(board, num, &col_masks[num], false, names, kind, accepts)
+            {
                 return Some(step);
             }
         }
@@ -48,22 +131,57 @@ pub fn find_fish_techniques(board: &LogicalBoard) -> Option<SolvingStep> {
     None
 }
 
-/// Generalized Fish Finder (X-Wing, Swordfish, Jellyfish)
+/// Searches for Kraken fish, smallest first: finned fish whose fins don't all see the
+/// target. An elimination in the cover lines is proven when every fin, if true, removes
+/// the candidate through a short chain of singles; otherwise the fins are all false and
+/// the fish itself removes it.
+pub fn find_kraken_fish(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let (row_masks, col_masks) = board.get_all_fish_masks();
+
+    for names in &FISH_SIZES {
+        for num in 1..=9 {
+            let kind = FishKind::Kraken;
+            if let Some(step) = check_fish(board, num, &row_masks[num], true, names, kind, accepts)
+            {
+                return Some(step);
+            }
+            if let Some(step) = check_fish(board, num, &col_masks[num], false, names, kind, accepts)
+            {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+/// Generalized Fish Finder (X-Wing, Swordfish, Jellyfish and their finned or Kraken variants)
 fn check_fish(
     board: &LogicalBoard,
     num: usize,
     masks: &[u16; 9],
-    size: usize,
     is_row_base: bool,
-    tech_name: &str,
+    names: &FishSize,
+    kind: FishKind,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
 ) -> Option<SolvingStep> {
-    // Filter rows/cols that have 2..size occurrences of the candidate
+    let size = names.size;
+    // Filter rows/cols that have 2..size occurrences of the candidate.
+    // Finned base lines may hold up to 3 extra fin candidates, and a Sashimi
+    // base line may have a single body candidate.
+    let (min_count, max_count) = if kind == FishKind::Basic {
+        (2, size)
+    } else {
+        (1, size + 3)
+    };
     let valid_indices: Vec<usize> = masks
         .iter()
         .enumerate()
         .filter(|&(_, m)| {
             let c = m.count_ones() as usize;
-            c >= 2 && c <= size
+            c >= min_count && c <= max_count
         })
         .map(|(i, _)| i)
         .collect();
@@ -78,7 +196,9 @@ fn check_fish(
         masks,
         size,
         is_row_base,
-        tech_name,
+        names,
+        kind,
+        accepts,
     };
 
     // Generate combinations of 'size' indices
@@ -99,6 +219,16 @@ fn find_fish_combo(
             union_mask |= ctx.masks[idx];
         }
 
+        if ctx.kind != FishKind::Basic {
+            // A basic fish is left to `find_fish_techniques`, and fins in a single box
+            // can add at most 3 lines to the union.
+            let union_count = union_mask.count_ones() as usize;
+            if union_count > ctx.size && union_count <= ctx.size + 3 {
+                return find_finned_cover(board, ctx, combo, union_mask);
+            }
+            return None;
+        }
+
         if union_mask.count_ones() as usize <= ctx.size {
             // Strictly speaking, fish requires N lines covered by N columns/rows.
             return construct_fish_step(
@@ -107,8 +237,9 @@ fn find_fish_combo(
                 combo,
                 union_mask,
                 ctx.is_row_base,
-                ctx.tech_name,
-            );
+                ctx.names.basic,
+            )
+            .filter(|step| (ctx.accepts)(step));
         }
         return None;
     }
@@ -130,7 +261,7 @@ fn construct_fish_step(
     base_indices: &[usize],
     union_mask: u16,
     is_row_base: bool,
-    tech_name: &str,
+    technique: Technique,
 ) -> Option<SolvingStep> {
     let cand_bit = 1 << (num - 1);
     let cover_indices: Vec<usize> = (0..9).filter(|&x| (union_mask & (1 << x)) != 0).collect();
@@ -156,7 +287,7 @@ fn construct_fish_step(
         None
     } else {
         Some(SolvingStep {
-            technique: tech_name.to_string(),
+            technique,
             placements: vec![],
             eliminations,
             cause: cause_cells,
@@ -187,6 +318,7 @@ fn collect_fish_causes(
                 cause_cells.push(CauseCell {
                     index: cell_idx,
                     candidates: vec![num],
+                    role: None,
                 });
             }
         }
@@ -228,3 +360,425 @@ fn collect_fish_eliminations(
     }
     eliminations
 }
+
+// --- Finned / Sashimi Fish ---
+
+/// Tries every choice of `size` cover lines from the base union, treating the remaining
+/// base candidates as fins.
+fn find_finned_cover(
+    board: &LogicalBoard,
+    ctx: &FishSearchContext,
+    base_indices: &[usize],
+    union_mask: u16,
+) -> Option<SolvingStep> {
+    // Enumerate all subsets of the union with exactly `size` lines.
+    let mut cover_mask = union_mask;
+    while cover_mask != 0 {
+        if cover_mask.count_ones() as usize == ctx.size {
+            let step = match ctx.kind {
+                FishKind::Kraken => {
+                    construct_kraken_fish_step(board, ctx, base_indices, cover_mask)
+                }
+                _ => construct_finned_fish_step(board, ctx, base_indices, cover_mask),
+            }
+            .filter(|step| (ctx.accepts)(step));
+            if step.is_some() {
+                return step;
+            }
+        }
+        cover_mask = (cover_mask - 1) & union_mask;
+    }
+    None
+}
+
+/// Constructs the Finned/Sashimi step if all fins share a box and eliminations exist.
+fn construct_finned_fish_step(
+    board: &LogicalBoard,
+    ctx: &FishSearchContext,
+    base_indices: &[usize],
+    cover_mask: u16,
+) -> Option<SolvingStep> {
+    // Every base line needs at least one body candidate inside the cover.
+    if base_indices.iter().any(|&b| ctx.masks[b] & cover_mask == 0) {
+        return None;
+    }
+
+    // Cheap pre-check: the fin lines must fall within a single band of 3 lines.
+    let fin_lines = ctx.masks.iter().enumerate().fold(0u16, |acc, (i, &m)| {
+        if base_indices.contains(&i) && m & !cover_mask != 0 {
+            acc | (1 << i)
+        } else {
+            acc
+        }
+    });
+    let fin_covers = base_indices
+        .iter()
+        .fold(0u16, |acc, &b| acc | (ctx.masks[b] & !cover_mask));
+    if !within_one_band(fin_lines) || !within_one_band(fin_covers) {
+        return None;
+    }
+
+    let to_cell = |base: usize, cover: usize| {
+        if ctx.is_row_base {
+            base * 9 + cover
+        } else {
+            cover * 9 + base
+        }
+    };
+
+    let fins: Vec<usize> = base_indices
+        .iter()
+        .flat_map(|&b| {
+            (0..9)
+                .filter(move |&x| ctx.masks[b] & !cover_mask & (1 << x) != 0)
+                .map(move |x| to_cell(b, x))
+        })
+        .collect();
+
+    let fin_box = get_box_index(fins[0]);
+    if fins.iter().any(|&f| get_box_index(f) != fin_box) {
+        return None;
+    }
+
+    let cand_bit = 1 << (ctx.num - 1);
+    let cover_indices: Vec<usize> = (0..9).filter(|&x| cover_mask & (1 << x) != 0).collect();
+
+    // Only cover cells in the fin box see every fin.
+    let eliminations: Vec<Elimination> = collect_fish_eliminations(
+        board,
+        cand_bit,
+        ctx.num,
+        base_indices,
+        &cover_indices,
+        ctx.is_row_base,
+    )
+    .into_iter()
+    .filter(|e| get_box_index(e.index) == fin_box)
+    .collect();
+
+    if eliminations.is_empty() {
+        return None;
+    }
+
+    // A Sashimi fish would be degenerate without its fins.
+    let is_sashimi = base_indices
+        .iter()
+        .any(|&b| (ctx.masks[b] & cover_mask).count_ones() == 1);
+    let technique = if is_sashimi {
+        ctx.names.sashimi
+    } else {
+        ctx.names.finned
+    };
+
+    let mut cause = collect_fish_causes(
+        board,
+        cand_bit,
+        ctx.num,
+        base_indices,
+        &cover_indices,
+        ctx.is_row_base,
+    );
+    cause.extend(fins.iter().map(|&f| CauseCell {
+        index: f,
+        candidates: vec![ctx.num],
+        role: Some(CauseRole::Fin),
+    }));
+
+    Some(SolvingStep {
+        technique,
+        placements: vec![],
+        eliminations,
+        cause,
+    })
+}
+
+// --- Kraken Fish ---
+
+/// Maximum number of fins of a Kraken fish; each one needs its own chain.
+const MAX_KRAKEN_FINS: usize = 3;
+
+/// Maximum number of rounds of singles in the chain from each fin.
+const MAX_KRAKEN_DEPTH: usize = 4;
+
+/// Constructs the Kraken step for the first cover elimination every fin reaches.
+fn construct_kraken_fish_step(
+    board: &LogicalBoard,
+    ctx: &FishSearchContext,
+    base_indices: &[usize],
+    cover_mask: u16,
+) -> Option<SolvingStep> {
+    if base_indices.iter().any(|&b| ctx.masks[b] & cover_mask == 0) {
+        return None;
+    }
+
+    let to_cell = |base: usize, cover: usize| {
+        if ctx.is_row_base {
+            base * 9 + cover
+        } else {
+            cover * 9 + base
+        }
+    };
+    let fins: Vec<usize> = base_indices
+        .iter()
+        .flat_map(|&b| {
+            (0..9)
+                .filter(move |&x| ctx.masks[b] & !cover_mask & (1 << x) != 0)
+                .map(move |x| to_cell(b, x))
+        })
+        .collect();
+    if fins.len() > MAX_KRAKEN_FINS {
+        return None;
+    }
+    // When the fins share a box, the targets in that box are a plain finned fish.
+    let fin_box = get_box_index(fins[0]);
+    let shared_box = fins
+        .iter()
+        .all(|&f| get_box_index(f) == fin_box)
+        .then_some(fin_box);
+
+    let cand_bit = 1 << (ctx.num - 1);
+    let cover_indices: Vec<usize> = (0..9).filter(|&x| cover_mask & (1 << x) != 0).collect();
+    let targets = collect_fish_eliminations(
+        board,
+        cand_bit,
+        ctx.num,
+        base_indices,
+        &cover_indices,
+        ctx.is_row_base,
+    );
+    if targets.is_empty() {
+        return None;
+    }
+
+    let branches: Vec<Branch> = fins
+        .iter()
+        .map(|&f| Branch::propagate(board, f, ctx.num, MAX_KRAKEN_DEPTH))
+        .collect();
+    let digit = ctx.num as usize - 1;
+    let target = targets
+        .into_iter()
+        .filter(|t| shared_box != Some(get_box_index(t.index)))
+        .find(|t| {
+            branches
+                .iter()
+                .all(|branch| branch.removed_by[t.index][digit].is_some())
+        })?;
+
+    let mut cause = collect_fish_causes(
+        board,
+        cand_bit,
+        ctx.num,
+        base_indices,
+        &cover_indices,
+        ctx.is_row_base,
+    );
+    cause.extend(fins.iter().map(|&f| CauseCell {
+        index: f,
+        candidates: vec![ctx.num],
+        role: Some(CauseRole::Fin),
+    }));
+    for branch in &branches {
+        let node = branch.removed_by[target.index][digit]?;
+        branch.append_tree(&mut cause, &[node]);
+    }
+
+    Some(SolvingStep {
+        technique: ctx.names.kraken,
+        placements: vec![],
+        eliminations: vec![target],
+        cause,
+    })
+}
+
+/// Checks whether all set lines of the mask lie in the same band of three.
+#[inline]
+fn within_one_band(mask: u16) -> bool {
+    (0..3).any(|band| mask & !(0b111 << (band * 3)) == 0)
+}
+
+// --- Franken / Mutant Fish ---
+
+/// Largest generalized fish searched by `find_franken_mutant_fish` (Jellyfish).
+pub const MAX_GENERALIZED_FISH_SIZE: usize = 4;
+
+struct GeneralFishContext<'a> {
+    num: u8,
+    size: usize,
+    names: &'a FishSize,
+    accepts: &'a dyn Fn(&SolvingStep) -> bool,
+    /// Bitset of the cells holding `num` for each unit of `ALL_UNITS`.
+    unit_cells: &'a [u128],
+}
+
+/// Searches for Franken and Mutant fish up to `MAX_GENERALIZED_FISH_SIZE`.
+/// This search is expensive, so it is not part of the default solving pipeline.
+pub fn find_franken_mutant_fish(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    find_generalized_fish(board, MAX_GENERALIZED_FISH_SIZE, accepts)
+}
+
+/// Generalized fish finder whose base and cover sets may be any units.
+/// `size` base units with disjoint candidates must each hold the digit, and the `size`
+/// cover units containing all of them can hold it only once each, so every other
+/// cover candidate is eliminated. Base candidates covered twice are eliminated too.
+/// Plain row/column fish are left to `find_fish_techniques`.
+pub fn find_generalized_fish(
+    board: &LogicalBoard,
+    max_size: usize,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    for names in FISH_SIZES.iter().filter(|names| names.size <= max_size) {
+        let size = names.size;
+        for num in 1..=9u8 {
+            let cand_bit = 1 << (num - 1);
+            let unit_cells: Vec<u128> = ALL_UNITS
+                .iter()
+                .map(|unit| {
+                    unit.iter()
+                        .filter(|&&idx| {
+                            board.cells[idx] == 0 && board.candidates[idx] & cand_bit != 0
+                        })
+                        .fold(0u128, |acc, &idx| acc | (1 << idx))
+                })
+                .collect();
+
+            let ctx = GeneralFishContext {
+                num,
+                size,
+                names,
+                accepts,
+                unit_cells: &unit_cells,
+            };
+            if let Some(step) = find_base_units(&ctx, 0, &mut Vec::with_capacity(size), 0) {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+/// Enumerates combinations of base units whose candidates do not overlap.
+fn find_base_units(
+    ctx: &GeneralFishContext,
+    start: usize,
+    bases: &mut Vec<usize>,
+    base_cells: u128,
+) -> Option<SolvingStep> {
+    if bases.len() == ctx.size {
+        return find_cover_units(ctx, bases, base_cells, &mut Vec::with_capacity(ctx.size), 0);
+    }
+
+    for unit in start..ctx.unit_cells.len() {
+        let cells = ctx.unit_cells[unit];
+        // A unit with a single position is a Hidden Single, not a fish.
+        if cells.count_ones() < 2 || cells & base_cells != 0 {
+            continue;
+        }
+        bases.push(unit);
+        if let Some(step) = find_base_units(ctx, unit + 1, bases, base_cells | cells) {
+            return Some(step);
+        }
+        bases.pop();
+    }
+    None
+}
+
+/// Picks cover units by always covering the first uncovered base cell with
+/// one of its row, column or box.
+fn find_cover_units(
+    ctx: &GeneralFishContext,
+    bases: &[usize],
+    base_cells: u128,
+    covers: &mut Vec<usize>,
+    covered: u128,
+) -> Option<SolvingStep> {
+    let uncovered = base_cells & !covered;
+    if uncovered == 0 {
+        // Fewer covers than bases would mean an invalid board.
+        if covers.len() == ctx.size {
+            return construct_generalized_fish_step(ctx, bases, covers, base_cells)
+                .filter(|step| (ctx.accepts)(step));
+        }
+        return None;
+    }
+    if covers.len() == ctx.size {
+        return None;
+    }
+
+    let cell = uncovered.trailing_zeros() as usize;
+    for unit in [cell / 9, 9 + cell % 9, 18 + get_box_index(cell)] {
+        if bases.contains(&unit) || covers.contains(&unit) {
+            continue;
+        }
+        covers.push(unit);
+        if let Some(step) = find_cover_units(
+            ctx,
+            bases,
+            base_cells,
+            covers,
+            covered | ctx.unit_cells[unit],
+        ) {
+            return Some(step);
+        }
+        covers.pop();
+    }
+    None
+}
+
+/// Classifies the fish and builds the step if it eliminates anything.
+fn construct_generalized_fish_step(
+    ctx: &GeneralFishContext,
+    bases: &[usize],
+    covers: &[usize],
+    base_cells: u128,
+) -> Option<SolvingStep> {
+    // Unit kinds in `ALL_UNITS`: 0 = row, 1 = column, 2 = box.
+    let has_kind = |units: &[usize], kind: usize| units.iter().any(|&u| u / 9 == kind);
+    let uses_boxes = has_kind(bases, 2) || has_kind(covers, 2);
+    let row_based = !has_kind(bases, 1) && !has_kind(covers, 0);
+    let col_based = !has_kind(bases, 0) && !has_kind(covers, 1);
+
+    let technique = match (row_based || col_based, uses_boxes) {
+        (true, false) => return None,
+        (true, true) => ctx.names.franken,
+        (false, _) => ctx.names.mutant,
+    };
+
+    let mut elim_cells = 0u128;
+    let mut covered_once = 0u128;
+    for &cover in covers {
+        let cells = ctx.unit_cells[cover];
+        elim_cells |= cells & !base_cells;
+        // Cannibalism: a base candidate in two covers would satisfy two covers at once.
+        elim_cells |= cells & base_cells & covered_once;
+        covered_once |= cells;
+    }
+    if elim_cells == 0 {
+        return None;
+    }
+
+    let eliminations = (0..81)
+        .filter(|&idx| elim_cells & (1 << idx) != 0)
+        .map(|idx| Elimination {
+            index: idx,
+            value: ctx.num,
+        })
+        .collect();
+    let cause = (0..81)
+        .filter(|&idx| base_cells & (1 << idx) != 0)
+        .map(|idx| CauseCell {
+            index: idx,
+            candidates: vec![ctx.num],
+            role: None,
+        })
+        .collect();
+
+    Some(SolvingStep {
+        technique,
+        placements: vec![],
+        eliminations,
+        cause,
+    })
+}
@@ -0,0 +1,424 @@
+/*
+* Copyright (C) 2025-2026  Henrique Almeida
+* This file is part of WASudoku.
+*
+* WASudoku is free software: you can redistribute it and/or modify
+* it under the terms of the GNU Affero General Public License as published
+* by the Free Software Foundation, either version 3 of the License, or
+* (at your option) any later version.
+*
+* WASudoku is distributed in the hope that it will be useful,
+* but WITHOUT ANY WARRANTY; without even the implied warranty of
+* MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+* GNU Affero General Public License for more details.
+*
+* You should have received a copy of the GNU Affero General Public License
+* along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use super::basic::collect_placement_eliminations;
+use super::{ALL_UNITS, LogicalBoard, PEER_MAP};
+use crate::types::{CauseCell, CauseRole, Elimination, Placement, SolvingStep, Technique};
+use std::collections::BTreeSet;
+
+/// Maximum number of rounds of singles a branch propagates before giving up.
+/// Keeps the search fast enough to run in the browser.
+pub const MAX_FORCING_DEPTH: usize = 8;
+
+/// A placement made while propagating a branch.
+pub(super) struct Node {
+    index: usize,
+    /// The placed digit, or `0` for a cell left without candidates.
+    value: u8,
+    /// The nodes whose removals implied this one; empty for the assumption.
+    parents: Vec<usize>,
+}
+
+/// The consequences of assuming a single placement, propagated with singles only.
+pub(super) struct Branch {
+    board: LogicalBoard,
+    nodes: Vec<Node>,
+    /// For every candidate removed by the branch, the node whose placement removed it.
+    /// The digit placed in a cell is not removed from it.
+    pub(super) removed_by: [[Option<usize>; 9]; 81],
+    /// The contradiction the branch ran into, if any.
+    contradiction: Option<Node>,
+}
+
+impl Branch {
+    /// Places `value` in `index` and propagates naked and hidden singles for at most
+    /// `max_depth` rounds, stopping at the first contradiction.
+    pub(super) fn propagate(
+        board: &LogicalBoard,
+        index: usize,
+        value: u8,
+        max_depth: usize,
+    ) -> Self {
+        let mut branch = Branch {
+            board: *board,
+            nodes: Vec::new(),
+            removed_by: [[None; 9]; 81],
+            contradiction: None,
+        };
+        branch.place(index, value, Vec::new());
+
+        for depth in 0..=max_depth {
+            if let Some(node) = branch.find_contradiction() {
+                branch.contradiction = Some(node);
+                break;
+            }
+            if depth == max_depth {
+                break;
+            }
+            let singles = branch.find_singles();
+            if singles.is_empty() {
+                break;
+            }
+            for (idx, val, parents) in singles {
+                // Conflicting singles leave a cell or unit empty and are caught next round.
+                if branch.board.candidates[idx] & (1 << (val - 1)) != 0 {
+                    branch.place(idx, val, parents);
+                }
+            }
+        }
+        branch
+    }
+
+    fn place(&mut self, index: usize, value: u8, parents: Vec<usize>) {
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            index,
+            value,
+            parents,
+        });
+
+        let bit = 1 << (value - 1);
+        for d in 0..9 {
+            if self.board.candidates[index] & !bit & (1 << d) != 0 {
+                self.removed_by[index][d].get_or_insert(node);
+            }
+        }
+        for &peer in &PEER_MAP[index] {
+            if self.board.candidates[peer] & bit != 0 {
+                self.removed_by[peer][value as usize - 1].get_or_insert(node);
+            }
+        }
+        self.board.set_cell(index, value);
+    }
+
+    /// Every node that removed one of the given candidates, in order. Candidates that
+    /// were already gone before the assumption are attributed to the assumption itself.
+    fn removals(&self, removed: impl Iterator<Item = (usize, usize)>) -> Vec<usize> {
+        let nodes: BTreeSet<usize> = removed
+            .filter_map(|(idx, d)| self.removed_by[idx][d])
+            .collect();
+        if nodes.is_empty() {
+            vec![0]
+        } else {
+            nodes.into_iter().collect()
+        }
+    }
+
+    /// Returns every naked and hidden single with the nodes that implied it.
+    fn find_singles(&self) -> Vec<(usize, u8, Vec<usize>)> {
+        let board = &self.board;
+        let mut singles = Vec::new();
+
+        for idx in 0..81 {
+            let mask = board.candidates[idx];
+            if board.cells[idx] == 0 && mask.count_ones() == 1 {
+                let d = mask.trailing_zeros() as usize;
+                let parents = self.removals((0..9).filter(|&o| o != d).map(|o| (idx, o)));
+                singles.push((idx, d as u8 + 1, parents));
+            }
+        }
+
+        for unit in ALL_UNITS.iter() {
+            for d in 0..9 {
+                let value = d as u8 + 1;
+                if unit.iter().any(|&idx| board.cells[idx] == value) {
+                    continue;
+                }
+                let mut positions = unit
+                    .iter()
+                    .filter(|&&idx| board.candidates[idx] & (1 << d) != 0);
+                if let (Some(&pos), None) = (positions.next(), positions.next()) {
+                    let parents =
+                        self.removals(unit.iter().filter(|&&idx| idx != pos).map(|&idx| (idx, d)));
+                    singles.push((pos, value, parents));
+                }
+            }
+        }
+        singles
+    }
+
+    /// Finds an empty cell without candidates or a digit without a place in a unit.
+    fn find_contradiction(&self) -> Option<Node> {
+        let board = &self.board;
+        for idx in 0..81 {
+            if board.cells[idx] == 0 && board.candidates[idx] == 0 {
+                return Some(Node {
+                    index: idx,
+                    value: 0,
+                    parents: self.removals((0..9).map(|d| (idx, d))),
+                });
+            }
+        }
+
+        for unit in ALL_UNITS.iter() {
+            for d in 0..9 {
+                let value = d as u8 + 1;
+                let missing = unit
+                    .iter()
+                    .all(|&idx| board.cells[idx] != value && board.candidates[idx] & (1 << d) == 0);
+                if missing {
+                    // Point at the cell that lost the last place for the digit.
+                    let index = unit
+                        .iter()
+                        .filter_map(|&idx| self.removed_by[idx][d].map(|node| (idx, node)))
+                        .max_by_key(|&(_, node)| node)
+                        .map_or(unit[0], |(idx, _)| idx);
+                    return Some(Node {
+                        index,
+                        value,
+                        parents: self.removals(unit.iter().map(|&idx| (idx, d))),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Appends to the cause the implication tree that leads from the assumption to the
+    /// `targets`: every node they depend on, each after the nodes that implied it.
+    /// Returns the positions of the `targets` in the cause.
+    pub(super) fn append_tree(&self, cause: &mut Vec<CauseCell>, targets: &[usize]) -> Vec<u16> {
+        let mut tree = BTreeSet::new();
+        let mut pending = targets.to_vec();
+        while let Some(node) = pending.pop() {
+            if tree.insert(node) {
+                pending.extend(&self.nodes[node].parents);
+            }
+        }
+
+        // Parents are always placed before their children, so node order is tree order.
+        let offset = cause.len();
+        let position = |node: usize| (offset + tree.range(..node).count()) as u16;
+        for &node in &tree {
+            let Node { index, value, .. } = self.nodes[node];
+            let role = if node == 0 {
+                CauseRole::Assumption
+            } else {
+                CauseRole::Implied(
+                    self.nodes[node]
+                        .parents
+                        .iter()
+                        .map(|&p| position(p))
+                        .collect(),
+                )
+            };
+            cause.push(CauseCell {
+                index,
+                candidates: vec![value],
+                role: Some(role),
+            });
+        }
+        targets.iter().map(|&node| position(node)).collect()
+    }
+}
+
+// --- Nishio Forcing Chains ---
+
+/// Searches for a candidate whose placement leads to a contradiction through singles
+/// alone, and eliminates it. The shortest contradiction found is reported.
+pub fn find_nishio_forcing_chain(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let mut best: Option<SolvingStep> = None;
+
+    for idx in 0..81 {
+        if board.cells[idx] != 0 || board.candidates[idx].count_ones() < 2 {
+            continue;
+        }
+        for d in 0..9 {
+            if board.candidates[idx] & (1 << d) == 0 {
+                continue;
+            }
+            let branch = Branch::propagate(board, idx, d as u8 + 1, MAX_FORCING_DEPTH);
+            let Some(contradiction) = &branch.contradiction else {
+                continue;
+            };
+            let step = construct_nishio_step(idx, d as u8 + 1, &branch, contradiction);
+            if accepts(&step)
+                && best
+                    .as_ref()
+                    .is_none_or(|b| step.cause.len() < b.cause.len())
+            {
+                best = Some(step);
+            }
+        }
+    }
+    best
+}
+
+/// Places `value` in `index` and propagates singles for at most `max_depth` rounds.
+/// If that leads to a contradiction, returns the Nishio step eliminating the candidate.
+pub fn find_contradiction_chain(
+    board: &LogicalBoard,
+    index: usize,
+    value: u8,
+    max_depth: usize,
+) -> Option<SolvingStep> {
+    let branch = Branch::propagate(board, index, value, max_depth);
+    let contradiction = branch.contradiction.as_ref()?;
+    Some(construct_nishio_step(index, value, &branch, contradiction))
+}
+
+/// The cause is the implication tree from the assumption, ending at the contradiction.
+fn construct_nishio_step(
+    index: usize,
+    value: u8,
+    branch: &Branch,
+    contradiction: &Node,
+) -> SolvingStep {
+    let mut cause = Vec::new();
+    let parents = branch.append_tree(&mut cause, &contradiction.parents);
+    cause.push(CauseCell {
+        index: contradiction.index,
+        candidates: match contradiction.value {
+            0 => vec![],
+            v => vec![v],
+        },
+        role: Some(CauseRole::Contradiction(parents)),
+    });
+
+    SolvingStep {
+        technique: Technique::NishioForcingChain,
+        placements: vec![],
+        eliminations: vec![Elimination { index, value }],
+        cause,
+    }
+}
+
+// --- Cell and Unit Forcing Chains ---
+
+/// Searches for a cell whose candidates, each assumed in turn, all force the same
+/// placement or elimination.
+pub fn find_cell_forcing_chain(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    for idx in 0..81 {
+        if board.cells[idx] != 0 || board.candidates[idx].count_ones() < 2 {
+            continue;
+        }
+        let assumptions: Vec<(usize, u8)> = (0..9)
+            .filter(|&d| board.candidates[idx] & (1 << d) != 0)
+            .map(|d| (idx, d as u8 + 1))
+            .collect();
+        if let Some(step) =
+            check_forcing_branches(board, Technique::CellForcingChain, &assumptions, accepts)
+        {
+            return Some(step);
+        }
+    }
+    None
+}
+
+/// Searches for a digit in a unit whose possible positions, each assumed in turn, all
+/// force the same placement or elimination.
+pub fn find_unit_forcing_chain(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    for unit in ALL_UNITS.iter() {
+        for d in 0..9 {
+            let assumptions: Vec<(usize, u8)> = unit
+                .iter()
+                .filter(|&&idx| board.candidates[idx] & (1 << d) != 0)
+                .map(|&idx| (idx, d as u8 + 1))
+                .collect();
+            if assumptions.len() < 2 {
+                continue;
+            }
+            if let Some(step) =
+                check_forcing_branches(board, Technique::UnitForcingChain, &assumptions, accepts)
+            {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+/// Propagates each assumption, one of which must be true, and looks for a placement
+/// or elimination shared by every branch.
+fn check_forcing_branches(
+    board: &LogicalBoard,
+    technique: Technique,
+    assumptions: &[(usize, u8)],
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let branches: Vec<Branch> = assumptions
+        .iter()
+        .map(|&(idx, value)| Branch::propagate(board, idx, value, MAX_FORCING_DEPTH))
+        .collect();
+    // A failing branch is the job of the Nishio search.
+    if branches.iter().any(|b| b.contradiction.is_some()) {
+        return None;
+    }
+
+    for idx in 0..81 {
+        if board.cells[idx] != 0 {
+            continue;
+        }
+        let value = branches[0].board.cells[idx];
+        if value != 0 && branches.iter().all(|b| b.board.cells[idx] == value) {
+            let mut cause = Vec::new();
+            for branch in &branches {
+                let node = branch.nodes.iter().position(|n| n.index == idx)?;
+                branch.append_tree(&mut cause, &[node]);
+            }
+            let step = SolvingStep {
+                technique,
+                placements: vec![Placement { index: idx, value }],
+                eliminations: collect_placement_eliminations(board, idx, value),
+                cause,
+            };
+            if accepts(&step) {
+                return Some(step);
+            }
+        }
+    }
+
+    for idx in 0..81 {
+        if board.cells[idx] != 0 {
+            continue;
+        }
+        for d in 0..9 {
+            let value = d as u8 + 1;
+            let eliminated = board.candidates[idx] & (1 << d) != 0
+                && branches.iter().all(|b| {
+                    b.board.cells[idx] != value && b.board.candidates[idx] & (1 << d) == 0
+                });
+            if eliminated {
+                let mut cause = Vec::new();
+                for branch in &branches {
+                    let node = branch.removed_by[idx][d]?;
+                    branch.append_tree(&mut cause, &[node]);
+                }
+                let step = SolvingStep {
+                    technique,
+                    placements: vec![],
+                    eliminations: vec![Elimination { index: idx, value }],
+                    cause,
+                };
+                if accepts(&step) {
+                    return Some(step);
+                }
+            }
+        }
+    }
+    None
+}
@@ -16,15 +16,18 @@
 * along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use super::{BOX_UNITS, COL_UNITS, LogicalBoard, ROW_UNITS};
-use crate::types::{CauseCell, Elimination, SolvingStep};
+use super::{BOX_UNITS, COL_UNITS, LogicalBoard, ROW_UNITS, mask_to_vec};
+use crate::types::{CauseCell, Elimination, SolvingStep, Technique};
 use std::collections::HashSet;
 
 // --- Pointing Subsets ---
 
 /// Searches for Pointing Pairs/Triples.
 /// A candidate in a box is confined to a single row or column -> eliminates from rest of row/col.
-pub fn find_pointing_subset(board: &LogicalBoard) -> Option<SolvingStep> {
+pub fn find_pointing_subset(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     for (box_idx, box_unit) in BOX_UNITS.iter().enumerate() {
         for num in 1..=9 {
             // Gather all cells in this box that have candidate 'num'
@@ -40,7 +43,9 @@ pub fn find_pointing_subset(board: &LogicalBoard) -> Option<SolvingStep> {
             }
 
             // Check alignment
-            if let Some(step) = check_pointing_alignment(board, &cells, box_idx, num) {
+            if let Some(step) = check_pointing_alignment(board, &cells, box_idx, num)
+                && accepts(&step)
+            {
                 return Some(step);
             }
         }
@@ -124,9 +129,9 @@ where
 fn build_pointing_step(cells: &[usize], elims: Vec<Elimination>, num: u8) -> SolvingStep {
     SolvingStep {
         technique: if cells.len() == 2 {
-            "PointingPair".into()
+            Technique::PointingPair
         } else {
-            "PointingTriple".into()
+            Technique::PointingTriple
         },
         placements: vec![],
         eliminations: elims,
@@ -135,6 +140,7 @@ fn build_pointing_step(cells: &[usize], elims: Vec<Elimination>, num: u8) -> Sol
             .map(|&i| CauseCell {
                 index: i,
                 candidates: vec![num],
+                role: None,
             })
             .collect(),
     }
@@ -144,16 +150,19 @@ fn build_pointing_step(cells: &[usize], elims: Vec<Elimination>, num: u8) -> Sol
 
 /// Searches for Claiming Candidates (Box-Line Reduction).
 /// A candidate in a row/col is confined to a single box -> eliminates from rest of box.
-pub fn find_claiming_candidates(board: &LogicalBoard) -> Option<SolvingStep> {
+pub fn find_claiming_candidates(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     // Check Rows
     for row in 0..9 {
-        if let Some(step) = find_claiming_in_unit(board, row, true) {
+        if let Some(step) = find_claiming_in_unit(board, row, true, accepts) {
             return Some(step);
         }
     }
     // Check Columns
     for col in 0..9 {
-        if let Some(step) = find_claiming_in_unit(board, col, false) {
+        if let Some(step) = find_claiming_in_unit(board, col, false, accepts) {
             return Some(step);
         }
     }
@@ -166,6 +175,7 @@ fn find_claiming_in_unit(
     board: &LogicalBoard,
     unit_idx: usize,
     is_row: bool,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
 ) -> Option<SolvingStep> {
     let unit = if is_row {
         &ROW_UNITS[unit_idx]
@@ -191,19 +201,24 @@ fn find_claiming_in_unit(
             let box_idx = *box_indices.iter().next().unwrap();
             let elims = collect_claiming_elims(board, box_idx, unit_idx, is_row, num, mask);
 
-            if !elims.is_empty() {
-                return Some(SolvingStep {
-                    technique: "ClaimingCandidate".into(),
-                    placements: vec![],
-                    eliminations: elims,
-                    cause: cells
-                        .iter()
-                        .map(|&i| CauseCell {
-                            index: i,
-                            candidates: vec![num],
-                        })
-                        .collect(),
-                });
+            if elims.is_empty() {
+                continue;
+            }
+            let step = SolvingStep {
+                technique: Technique::ClaimingCandidate,
+                placements: vec![],
+                eliminations: elims,
+                cause: cells
+                    .iter()
+                    .map(|&i| CauseCell {
+                        index: i,
+                        candidates: vec![num],
+                        role: None,
+                    })
+                    .collect(),
+            };
+            if accepts(&step) {
+                return Some(step);
             }
         }
     }
@@ -238,3 +253,150 @@ fn collect_claiming_elims(
     }
     elims
 }
+
+// --- Sue de Coq ---
+
+/// Searches for Sue de Coq (two-sector disjoint subsets).
+/// Two or three intersection cells of a box and a line hold at least two more candidates
+/// than cells. Some line-only cells (with candidates V_L) and some box-only cells (with
+/// candidates V_B, disjoint from V_L) complete them into N cells holding exactly N digits.
+/// All of those digits are then placed in these cells: digits outside V_B lie within the
+/// line and digits outside V_L within the box, so they leave the rest of each sector.
+pub fn find_sue_de_coq(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    for (box_idx, box_unit) in BOX_UNITS.iter().enumerate() {
+        let band = box_idx / 3;
+        let stack = box_idx % 3;
+        for k in 0..3 {
+            if let Some(step) = check_sue_de_coq(board, box_unit, &ROW_UNITS[band * 3 + k], accepts)
+            {
+                return Some(step);
+            }
+            if let Some(step) =
+                check_sue_de_coq(board, box_unit, &COL_UNITS[stack * 3 + k], accepts)
+            {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+fn check_sue_de_coq(
+    board: &LogicalBoard,
+    box_unit: &[usize; 9],
+    line: &[usize; 9],
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let empty = |idx: &&usize| board.cells[**idx] == 0;
+    let intersection: Vec<usize> = line
+        .iter()
+        .filter(|idx| box_unit.contains(idx))
+        .filter(empty)
+        .cloned()
+        .collect();
+    if intersection.len() < 2 {
+        return None;
+    }
+
+    for (core, core_mask) in cell_subsets(board, &intersection) {
+        if core.len() < 2 || (core_mask.count_ones() as usize) < core.len() + 2 {
+            continue;
+        }
+        // Only cells sharing a digit with the core can help lock it.
+        let pool = |unit: &[usize; 9], other: &[usize; 9]| -> Vec<usize> {
+            unit.iter()
+                .filter(|idx| !other.contains(idx))
+                .filter(empty)
+                .filter(|&&idx| board.candidates[idx] & core_mask != 0)
+                .cloned()
+                .collect()
+        };
+        let line_sets = cell_subsets(board, &pool(line, box_unit));
+        let box_sets = cell_subsets(board, &pool(box_unit, line));
+
+        for (line_cells, line_mask) in &line_sets {
+            for (box_cells, box_mask) in &box_sets {
+                if line_mask & box_mask != 0 {
+                    continue;
+                }
+                let all_mask = core_mask | line_mask | box_mask;
+                let cell_count = core.len() + line_cells.len() + box_cells.len();
+                if all_mask.count_ones() as usize != cell_count {
+                    continue;
+                }
+
+                let used: Vec<usize> = core
+                    .iter()
+                    .chain(line_cells)
+                    .chain(box_cells)
+                    .cloned()
+                    .collect();
+                let mut elims = collect_sector_elims(board, line, &used, all_mask & !box_mask);
+                elims.extend(collect_sector_elims(
+                    board,
+                    box_unit,
+                    &used,
+                    all_mask & !line_mask,
+                ));
+                if elims.is_empty() {
+                    continue;
+                }
+
+                let step = SolvingStep {
+                    technique: Technique::SueDeCoq,
+                    placements: vec![],
+                    eliminations: elims,
+                    cause: used
+                        .iter()
+                        .map(|&i| CauseCell {
+                            index: i,
+                            candidates: mask_to_vec(board.candidates[i]),
+                            role: None,
+                        })
+                        .collect(),
+                };
+                if accepts(&step) {
+                    return Some(step);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Lists every non-empty subset of `cells` with the union of its candidates.
+fn cell_subsets(board: &LogicalBoard, cells: &[usize]) -> Vec<(Vec<usize>, u16)> {
+    (1u32..(1 << cells.len()))
+        .map(|subset| {
+            let chosen: Vec<usize> = (0..cells.len())
+                .filter(|&i| subset & (1 << i) != 0)
+                .map(|i| cells[i])
+                .collect();
+            let mask = chosen.iter().fold(0, |acc, &i| acc | board.candidates[i]);
+            (chosen, mask)
+        })
+        .collect()
+}
+
+/// Eliminates the digits of `mask` from the cells of `unit` outside `used`.
+#[inline]
+fn collect_sector_elims(
+    board: &LogicalBoard,
+    unit: &[usize; 9],
+    used: &[usize],
+    mask: u16,
+) -> Vec<Elimination> {
+    let mut elims = Vec::new();
+    for &idx in unit {
+        if used.contains(&idx) || board.cells[idx] != 0 {
+            continue;
+        }
+        for value in mask_to_vec(board.candidates[idx] & mask) {
+            elims.push(Elimination { index: idx, value });
+        }
+    }
+    elims
+}
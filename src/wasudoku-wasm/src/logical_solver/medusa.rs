@@ -0,0 +1,266 @@
+/*
+* Copyright (C) 2025-2026  Henrique Almeida
+* This file is part of WASudoku.
+*
+* WASudoku is free software: you can redistribute it and/or modify
+* it under the terms of the GNU Affero General Public License as published
+* by the Free Software Foundation, either version 3 of the License, or
+* (at your option) any later version.
+*
+* WASudoku is distributed in the hope that it will be useful,
+* but WITHOUT ANY WARRANTY; without even the implied warranty of
+* MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+* GNU Affero General Public License for more details.
+*
+* You should have received a copy of the GNU Affero General Public License
+* along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! 3D Medusa: two-coloring of candidates across conjugate pairs and bivalue cells.
+
+use super::{ALL_UNITS, LogicalBoard, PEER_MAP, mask_to_vec};
+use crate::types::{CauseCell, CauseRole, Elimination, Placement, SolvingStep, Technique};
+
+/// A candidate node is encoded as `cell * 9 + (digit - 1)`.
+const NODE_COUNT: usize = 81 * 9;
+
+/// The candidates of one colored component, as digit masks per cell for each color.
+struct MedusaColoring {
+    masks: [[u16; 81]; 2],
+}
+
+impl MedusaColoring {
+    #[inline]
+    fn has(&self, color: usize, cell: usize, bit: u16) -> bool {
+        (self.masks[color][cell] & bit) != 0
+    }
+
+    #[inline]
+    fn colored(&self, cell: usize) -> u16 {
+        self.masks[0][cell] | self.masks[1][cell]
+    }
+
+    /// Whether `cell` sees a candidate `bit` of the given color.
+    fn sees(&self, color: usize, cell: usize, bit: u16) -> bool {
+        PEER_MAP[cell].iter().any(|&p| self.has(color, p, bit))
+    }
+}
+
+/// Searches for 3D Medusa deductions using the six standard rules.
+/// Rules 1, 2 and 6 prove a color false, which places every candidate of the opposite
+/// color; rules 3, 4 and 5 eliminate uncolored candidates.
+pub fn find_3d_medusa(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let links = build_medusa_links(board);
+    let mut visited = [false; NODE_COUNT];
+
+    for start in 0..NODE_COUNT {
+        if visited[start] || links[start].is_empty() {
+            continue;
+        }
+        let coloring = color_component(&links, start, &mut visited);
+        if let Some(step) = check_medusa_component(board, &coloring, accepts) {
+            return Some(step);
+        }
+    }
+    None
+}
+
+/// Strong links between candidates: conjugate pairs in a unit and the two
+/// candidates of a bivalue cell.
+fn build_medusa_links(board: &LogicalBoard) -> Vec<Vec<usize>> {
+    let mut links = vec![Vec::new(); NODE_COUNT];
+    let mut add_link = |a: usize, b: usize| {
+        if !links[a].contains(&b) {
+            links[a].push(b);
+            links[b].push(a);
+        }
+    };
+
+    for unit in ALL_UNITS.iter() {
+        for num in 1..=9u8 {
+            let bit = 1 << (num - 1);
+            let positions: Vec<usize> = unit
+                .iter()
+                .filter(|&&idx| board.cells[idx] == 0 && (board.candidates[idx] & bit) != 0)
+                .cloned()
+                .collect();
+            if positions.len() == 2 {
+                let d = (num - 1) as usize;
+                add_link(positions[0] * 9 + d, positions[1] * 9 + d);
+            }
+        }
+    }
+
+    for idx in 0..81 {
+        if board.cells[idx] == 0 && board.candidates[idx].count_ones() == 2 {
+            let digits = mask_to_vec(board.candidates[idx]);
+            add_link(
+                idx * 9 + (digits[0] - 1) as usize,
+                idx * 9 + (digits[1] - 1) as usize,
+            );
+        }
+    }
+    links
+}
+
+fn color_component(
+    links: &[Vec<usize>],
+    start: usize,
+    visited: &mut [bool; NODE_COUNT],
+) -> MedusaColoring {
+    let mut coloring = MedusaColoring {
+        masks: [[0; 81]; 2],
+    };
+    let mut stack = vec![(start, 0)];
+    visited[start] = true;
+
+    while let Some((node, color)) = stack.pop() {
+        coloring.masks[color][node / 9] |= 1 << (node % 9);
+        for &next in &links[node] {
+            if !visited[next] {
+                visited[next] = true;
+                stack.push((next, 1 - color));
+            }
+        }
+    }
+    coloring
+}
+
+fn check_medusa_component(
+    board: &LogicalBoard,
+    coloring: &MedusaColoring,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    for color in 0..2 {
+        if color_is_false(board, coloring, color) {
+            let step = construct_contradiction_step(coloring, color);
+            if accepts(&step) {
+                return Some(step);
+            }
+        }
+    }
+
+    let mut eliminations = Vec::new();
+    for idx in 0..81 {
+        if board.cells[idx] != 0 {
+            continue;
+        }
+        let uncolored = board.candidates[idx] & !coloring.colored(idx);
+        if uncolored == 0 {
+            continue;
+        }
+
+        // Rule 3: a cell holding both colors keeps only its colored candidates.
+        if coloring.masks[0][idx] != 0 && coloring.masks[1][idx] != 0 {
+            eliminations.extend(
+                mask_to_vec(uncolored)
+                    .into_iter()
+                    .map(|value| Elimination { index: idx, value }),
+            );
+            continue;
+        }
+
+        for value in mask_to_vec(uncolored) {
+            let bit = 1 << (value - 1);
+            let sees_0 = coloring.sees(0, idx, bit);
+            let sees_1 = coloring.sees(1, idx, bit);
+            // Rule 4: the candidate sees both colors of the same digit.
+            // Rule 5: the candidate sees one color and its cell holds the other.
+            if (sees_0 && (sees_1 || coloring.masks[1][idx] != 0))
+                || (sees_1 && coloring.masks[0][idx] != 0)
+            {
+                eliminations.push(Elimination { index: idx, value });
+            }
+        }
+    }
+
+    if eliminations.is_empty() {
+        return None;
+    }
+    Some(SolvingStep {
+        technique: Technique::Medusa3D,
+        placements: vec![],
+        eliminations,
+        cause: build_medusa_cause(coloring),
+    })
+    .filter(|step| accepts(step))
+}
+
+/// Rules 1, 2 and 6: checks whether assuming `color` true leads to a contradiction.
+fn color_is_false(board: &LogicalBoard, coloring: &MedusaColoring, color: usize) -> bool {
+    let masks = &coloring.masks[color];
+
+    // Rule 1: two candidates of the same color in one cell.
+    if masks.iter().any(|m| m.count_ones() >= 2) {
+        return true;
+    }
+
+    // Rule 2: the same digit twice with the same color in one unit.
+    let twice_in_unit = ALL_UNITS.iter().any(|unit| {
+        let mut seen = 0u16;
+        unit.iter().any(|&idx| {
+            let twice = (seen & masks[idx]) != 0;
+            seen |= masks[idx];
+            twice
+        })
+    });
+    if twice_in_unit {
+        return true;
+    }
+
+    // Rule 6: an uncolored cell whose every candidate sees that digit in this color.
+    (0..81).any(|idx| {
+        board.cells[idx] == 0
+            && coloring.colored(idx) == 0
+            && mask_to_vec(board.candidates[idx])
+                .into_iter()
+                .all(|value| coloring.sees(color, idx, 1 << (value - 1)))
+    })
+}
+
+/// A false color: its candidates are eliminated and the opposite color is placed.
+fn construct_contradiction_step(coloring: &MedusaColoring, false_color: usize) -> SolvingStep {
+    let true_masks = &coloring.masks[1 - false_color];
+    let mut placements = Vec::new();
+    let mut eliminations = Vec::new();
+
+    for (idx, &true_mask) in true_masks.iter().enumerate() {
+        if true_mask != 0 {
+            placements.push(Placement {
+                index: idx,
+                value: (true_mask.trailing_zeros() + 1) as u8,
+            });
+        } else {
+            eliminations.extend(
+                mask_to_vec(coloring.masks[false_color][idx])
+                    .into_iter()
+                    .map(|value| Elimination { index: idx, value }),
+            );
+        }
+    }
+
+    SolvingStep {
+        technique: Technique::Medusa3D,
+        placements,
+        eliminations,
+        cause: build_medusa_cause(coloring),
+    }
+}
+
+/// One cause entry per colored candidate, so a cell can carry both colors.
+fn build_medusa_cause(coloring: &MedusaColoring) -> Vec<CauseCell> {
+    let mut cause = Vec::new();
+    for idx in 0..81 {
+        for (color, masks) in coloring.masks.iter().enumerate() {
+            cause.extend(mask_to_vec(masks[idx]).into_iter().map(|value| CauseCell {
+                index: idx,
+                candidates: vec![value],
+                role: Some(CauseRole::Color(color as u8)),
+            }));
+        }
+    }
+    cause
+}
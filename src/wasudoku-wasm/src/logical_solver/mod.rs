@@ -19,17 +19,28 @@
 //! A logical Sudoku solver that uses human-like techniques.
 //! This module acts as the orchestrator, delegating specific technique checks to submodules.
 
+pub mod aic;
+pub mod als;
 pub mod basic;
+pub mod chains;
+pub mod coloring;
+pub mod exclusion;
+pub mod exocet;
+pub mod explain;
 pub mod fish;
+pub mod forcing;
 pub mod intersection;
+pub mod medusa;
 pub mod single_digit;
 pub mod subsets;
+pub mod templates;
 pub mod uniqueness;
 pub mod wings;
 
 use crate::board::Board;
-use crate::types::SolvingStep;
+use crate::types::{SolvingStep, Technique};
 use std::collections::HashSet;
+use std::str::FromStr;
 
 /// Bitmask representing all candidates (1-9) for a cell.
 pub(crate) const ALL_CANDIDATES: u16 = 0b111111111;
@@ -97,14 +108,21 @@ lazy_static::lazy_static! {
     };
 }
 
+/// Returns the index (0-8) of the box containing a cell.
+#[inline]
+pub(crate) fn get_box_index(idx: usize) -> usize {
+    (idx / 27) * 3 + (idx % 9) / 3
+}
+
 /// Represents the logical difficulty of a solving technique.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TechniqueLevel {
     None,         // No logical moves found
     Basic,        // Naked/Hidden Singles
     Intermediate, // Pointing Subsets, Naked/Hidden Pairs/Triples, Box-Line Reduction
-    Advanced,     // X-Wing, Swordfish, XY-Wing, XYZ-Wing, Skyscraper, 2-String Kite
-    Master,       // Jellyfish, Unique Rectangle, W-Wing
+    Advanced,     // Quads, Fish, Finned Fish, XY/XYZ-Wing, Kite, ER, Simple Coloring, APE
+    Master, // Jellyfish, UR, BUG+1, W-Wing, ATE, Multi-Coloring, Medusa, Chains, AIC, ALS, Kraken
+    Extreme, // Exocet, Templates, Forcing Chains
 }
 
 /// Stats for difficulty analysis
@@ -113,6 +131,7 @@ pub struct DifficultyStats {
     pub intermediate_count: usize,
     pub advanced_count: usize,
     pub master_count: usize,
+    pub extreme_count: usize,
 }
 
 /// Convert a bitmask of candidates into a `Vec` of numbers.
@@ -157,6 +176,80 @@ impl LogicalBoard {
         logical_board
     }
 
+    /// Initializes a LogicalBoard from a board and the player's pencil marks, one
+    /// candidate mask per cell. Marks ruled out by a placed peer are dropped, and an
+    /// empty cell without marks gets every candidate it can hold.
+    ///
+    /// ### Errors
+    ///
+    /// Returns an `Err` like `from_candidate_grid` if the remaining marks are
+    /// impossible, e.g. a cell only marked with digits placed in its peers.
+    pub fn from_candidates(board: &Board, masks: &[u16; 81]) -> Result<Self, String> {
+        let mut candidates = Self::from_board(board).candidates;
+        for (candidates, &mask) in candidates.iter_mut().zip(masks) {
+            if mask & ALL_CANDIDATES != 0 {
+                *candidates &= mask;
+            }
+        }
+        Self::from_candidate_grid(board.cells, candidates)
+    }
+
+    /// Initializes a LogicalBoard from placed digits and the candidates of the empty
+    /// cells, such as a position whose candidates were edited by hand. Candidate masks
+    /// of filled cells are ignored.
+    ///
+    /// ### Errors
+    ///
+    /// Returns an `Err` if a value is out of range, placed digits conflict, an empty
+    /// cell has no candidates or holds a digit placed in a peer, or a digit has no
+    /// place left in a unit.
+    pub fn from_candidate_grid(cells: [u8; 81], candidates: [u16; 81]) -> Result<Self, String> {
+        if let Some(i) = (0..81).find(|&i| cells[i] > 9) {
+            return Err(format!("Invalid value {} at cell {}", cells[i], i));
+        }
+        let board: Board = Board { cells }.to_string().parse()?;
+        let reference = Self::from_board(&board);
+
+        let mut logical_board = LogicalBoard {
+            cells,
+            candidates: [0; 81],
+        };
+        for i in (0..81).filter(|&i| cells[i] == 0) {
+            let mask = candidates[i];
+            if mask == 0 || mask & !ALL_CANDIDATES != 0 {
+                return Err(format!("Invalid candidates at cell {}", i));
+            }
+            let taken = mask & !reference.candidates[i];
+            if taken != 0 {
+                return Err(format!(
+                    "Invalid candidates: {} at cell {} is already placed in a peer",
+                    taken.trailing_zeros() + 1,
+                    i
+                ));
+            }
+            logical_board.candidates[i] = mask;
+        }
+
+        for (u, unit) in ALL_UNITS.iter().enumerate() {
+            for digit in 1..=9u8 {
+                let bit = 1 << (digit - 1);
+                let has_place = unit
+                    .iter()
+                    .any(|&i| cells[i] == digit || logical_board.candidates[i] & bit != 0);
+                if !has_place {
+                    let kind = ["row", "column", "box"][u / 9];
+                    return Err(format!(
+                        "Invalid candidates: {} has no place left in {} {}",
+                        digit,
+                        kind,
+                        u % 9 + 1
+                    ));
+                }
+            }
+        }
+        Ok(logical_board)
+    }
+
     /// Sets a cell value and eliminates that value from peers. Returns true if successful.
     pub fn set_cell(&mut self, index: usize, value: u8) -> bool {
         if self.cells[index] != 0 {
@@ -201,35 +294,350 @@ impl LogicalBoard {
     }
 }
 
+/// Parse a pencilmark grid, as exported by HoDoKu or SudokuWiki, into a `LogicalBoard`.
+///
+/// The grid lists the 81 cells row by row as groups of digits: the candidates of an
+/// empty cell, or a placed value. A single digit is read as a placed value unless a
+/// peer still lists it as a candidate. Any other characters, such as the `|`, `-` and
+/// `+` of the grid borders, separate cells.
+///
+/// ### Errors
+///
+/// Returns an `Err` if the grid does not have 81 cells, uses the digit `0`, or
+/// describes an impossible position (see `LogicalBoard::from_candidate_grid`).
+impl FromStr for LogicalBoard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens: Vec<&str> = s
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|token| !token.is_empty())
+            .collect();
+        if tokens.len() != 81 {
+            return Err(format!(
+                "Invalid candidate grid: expected 81 cells, got {}",
+                tokens.len()
+            ));
+        }
+
+        let mut masks = [0u16; 81];
+        for (i, token) in tokens.iter().enumerate() {
+            for digit in token.bytes().map(|b| b - b'0') {
+                if digit == 0 {
+                    return Err(format!("Invalid digit '0' in candidate grid at cell {}", i));
+                }
+                masks[i] |= 1 << (digit - 1);
+            }
+        }
+
+        let mut cells = [0; 81];
+        let mut candidates = [0; 81];
+        for i in 0..81 {
+            let mask = masks[i];
+            // A lone digit still listed by a peer is an unplaced single, not a value.
+            let is_placed = mask.count_ones() == 1
+                && tokens[i].len() == 1
+                && PEER_MAP[i]
+                    .iter()
+                    .all(|&peer| tokens[peer].len() == 1 || masks[peer] & mask == 0);
+            if is_placed {
+                cells[i] = mask.trailing_zeros() as u8 + 1;
+            } else {
+                candidates[i] = mask;
+            }
+        }
+        LogicalBoard::from_candidate_grid(cells, candidates)
+    }
+}
+
+/// A technique finder: returns the first step it can make on the board that `accepts`
+/// takes, searching on past the steps it rejects.
+pub type TechniqueFinder = fn(&LogicalBoard, &dyn Fn(&SolvingStep) -> bool) -> Option<SolvingStep>;
+
+/// Every technique finder, in the default order of complexity/speed, with every
+/// technique it can report.
+pub const PIPELINE: [(TechniqueFinder, &[Technique]); 47] = [
+    (basic::find_naked_single, &[Technique::NakedSingle]),
+    (basic::find_hidden_single, &[Technique::HiddenSingle]),
+    (subsets::find_naked_pair, &[Technique::NakedPair]),
+    (subsets::find_naked_triple, &[Technique::NakedTriple]),
+    (
+        intersection::find_pointing_subset,
+        &[Technique::PointingPair, Technique::PointingTriple],
+    ),
+    (subsets::find_hidden_pair, &[Technique::HiddenPair]),
+    (subsets::find_hidden_triple, &[Technique::HiddenTriple]),
+    (
+        intersection::find_claiming_candidates,
+        &[Technique::ClaimingCandidate],
+    ),
+    // Advanced Techniques
+    (subsets::find_naked_quad, &[Technique::NakedQuad]),
+    (subsets::find_hidden_quad, &[Technique::HiddenQuad]),
+    (
+        fish::find_fish_techniques,
+        &[Technique::XWing, Technique::Swordfish, Technique::Jellyfish],
+    ),
+    (wings::find_xy_wing, &[Technique::XYWing]),
+    (wings::find_xyz_wing, &[Technique::XYZWing]),
+    (single_digit::find_skyscraper, &[Technique::Skyscraper]),
+    (
+        single_digit::find_two_string_kite,
+        &[Technique::TwoStringKite],
+    ),
+    (
+        fish::find_finned_fish,
+        &[
+            Technique::FinnedXWing,
+            Technique::SashimiXWing,
+            Technique::FinnedSwordfish,
+            Technique::SashimiSwordfish,
+            Technique::FinnedJellyfish,
+            Technique::SashimiJellyfish,
+        ],
+    ),
+    (
+        single_digit::find_empty_rectangle,
+        &[Technique::EmptyRectangle],
+    ),
+    (
+        coloring::find_simple_coloring,
+        &[Technique::SimpleColoringTrap, Technique::SimpleColoringWrap],
+    ),
+    (chains::find_remote_pairs, &[Technique::RemotePair]),
+    (
+        exclusion::find_aligned_pair_exclusion,
+        &[Technique::AlignedPairExclusion],
+    ),
+    // Master Techniques
+    (
+        uniqueness::find_unique_rectangle_type_1,
+        &[Technique::UniqueRectangleType1],
+    ),
+    (
+        uniqueness::find_unique_rectangle_type_2,
+        &[Technique::UniqueRectangleType2],
+    ),
+    (
+        uniqueness::find_unique_rectangle_type_4,
+        &[Technique::UniqueRectangleType4],
+    ),
+    (
+        uniqueness::find_unique_rectangle_type_5,
+        &[Technique::UniqueRectangleType5],
+    ),
+    (
+        uniqueness::find_unique_rectangle_type_3,
+        &[Technique::UniqueRectangleType3],
+    ),
+    (
+        uniqueness::find_unique_rectangle_type_6,
+        &[Technique::UniqueRectangleType6],
+    ),
+    (
+        uniqueness::find_hidden_unique_rectangle,
+        &[Technique::HiddenUniqueRectangle],
+    ),
+    (uniqueness::find_bug_plus_one, &[Technique::BugPlusOne]),
+    (wings::find_w_wing, &[Technique::WWing]),
+    (coloring::find_multi_coloring, &[Technique::MultiColoring]),
+    (medusa::find_3d_medusa, &[Technique::Medusa3D]),
+    (chains::find_x_chain, &[Technique::XChain]),
+    (chains::find_xy_chain, &[Technique::XYChain]),
+    (
+        aic::find_aic,
+        &[
+            Technique::AicType1,
+            Technique::AicType2,
+            Technique::DiscontinuousNiceLoop,
+        ],
+    ),
+    (
+        als::find_als_xz,
+        &[Technique::AlsXz, Technique::DoublyLinkedAlsXz],
+    ),
+    (als::find_als_xy_wing, &[Technique::AlsXyWing]),
+    (als::find_death_blossom, &[Technique::DeathBlossom]),
+    (intersection::find_sue_de_coq, &[Technique::SueDeCoq]),
+    (
+        wings::find_bent_naked_subset,
+        &[
+            Technique::XYWing,
+            Technique::XYZWing,
+            Technique::WXYZWing,
+            Technique::VWXYZWing,
+        ],
+    ),
+    (
+        exclusion::find_aligned_triple_exclusion,
+        &[Technique::AlignedTripleExclusion],
+    ),
+    (
+        fish::find_kraken_fish,
+        &[
+            Technique::KrakenXWing,
+            Technique::KrakenSwordfish,
+            Technique::KrakenJellyfish,
+        ],
+    ),
+    // Opt-in Techniques
+    (
+        fish::find_franken_mutant_fish,
+        &[
+            Technique::FrankenXWing,
+            Technique::FrankenSwordfish,
+            Technique::FrankenJellyfish,
+            Technique::MutantXWing,
+            Technique::MutantSwordfish,
+            Technique::MutantJellyfish,
+        ],
+    ),
+    // Extreme Techniques
+    (exocet::find_junior_exocet, &[Technique::JuniorExocet]),
+    (
+        templates::find_templates,
+        &[Technique::TemplatePlacement, Technique::TemplateElimination],
+    ),
+    (
+        forcing::find_nishio_forcing_chain,
+        &[Technique::NishioForcingChain],
+    ),
+    (
+        forcing::find_cell_forcing_chain,
+        &[Technique::CellForcingChain],
+    ),
+    (
+        forcing::find_unit_forcing_chain,
+        &[Technique::UnitForcingChain],
+    ),
+];
+
+/// Techniques that are too expensive for the default pipeline. Add them to
+/// `SolverConfig::techniques` to use them.
+const OPT_IN_TECHNIQUES: [Technique; 6] = [
+    Technique::FrankenXWing,
+    Technique::FrankenSwordfish,
+    Technique::FrankenJellyfish,
+    Technique::MutantXWing,
+    Technique::MutantSwordfish,
+    Technique::MutantJellyfish,
+];
+
+/// Which techniques the solver may use, and in which order it tries them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolverConfig {
+    /// The enabled techniques, in priority order. A finder of the `PIPELINE` runs at
+    /// the position of the first technique it searches for, and only reports steps of
+    /// enabled techniques.
+    pub techniques: Vec<Technique>,
+    /// Techniques above this level are skipped, even if enabled.
+    pub max_level: TechniqueLevel,
+}
+
+impl Default for SolverConfig {
+    /// Every technique of the default pipeline, in its default order. The Extreme
+    /// techniques come last, so they only run once everything else is stuck.
+    fn default() -> Self {
+        let techniques = PIPELINE
+            .iter()
+            .flat_map(|(_, techniques)| techniques.iter().copied())
+            .filter(|t| !OPT_IN_TECHNIQUES.contains(t))
+            .collect();
+        SolverConfig {
+            techniques,
+            max_level: TechniqueLevel::Extreme,
+        }
+    }
+}
+
+impl SolverConfig {
+    /// The default techniques up to `max_level`, in the default order.
+    pub fn up_to(max_level: TechniqueLevel) -> Self {
+        SolverConfig {
+            max_level,
+            ..Self::default()
+        }
+    }
+
+    /// Whether steps of `technique` may be used.
+    pub fn allows(&self, technique: Technique) -> bool {
+        technique.level() <= self.max_level && self.techniques.contains(&technique)
+    }
+
+    /// The finders to run, in order.
+    pub fn finders(&self) -> Vec<TechniqueFinder> {
+        self.stages().into_iter().map(|i| PIPELINE[i].0).collect()
+    }
+
+    /// The positions in `PIPELINE` of the finders to run, in order. A technique that
+    /// several finders report, like XY-Wing, is scheduled with the first of them only.
+    fn stages(&self) -> Vec<usize> {
+        let mut stages = Vec::new();
+        for &technique in self.techniques.iter().filter(|&&t| self.allows(t)) {
+            let first = PIPELINE.iter().position(|(_, t)| t.contains(&technique));
+            if let Some(i) = first
+                && !stages.contains(&i)
+            {
+                stages.push(i);
+            }
+        }
+        stages
+    }
+}
+
 /// Solve the board by repeatedly applying logical techniques and return the steps.
 pub fn solve_with_steps(initial_board: &Board) -> (Vec<SolvingStep>, Board) {
+    solve_with_config(initial_board, &SolverConfig::default())
+}
+
+/// Like `solve_with_steps`, but only uses the techniques enabled by `config`, in its order.
+pub fn solve_with_config(
+    initial_board: &Board,
+    config: &SolverConfig,
+) -> (Vec<SolvingStep>, Board) {
+    run_finders(initial_board, &config.finders(), |step| {
+        config.allows(step.technique)
+    })
+}
+
+/// Returns the simplest step `config` allows on `board`, without applying it.
+///
+/// Levels are tried from easiest to hardest, so a step is only returned when no
+/// allowed technique of a lower level applies, whatever the order of `config`.
+pub fn find_next_step(board: &LogicalBoard, config: &SolverConfig) -> Option<SolvingStep> {
+    let mut levels: Vec<TechniqueLevel> = config
+        .techniques
+        .iter()
+        .filter(|&&t| config.allows(t))
+        .map(|t| t.level())
+        .collect();
+    levels.sort();
+    levels.dedup();
+
+    let stages = config.stages();
+    levels.into_iter().find_map(|level| {
+        let accepts =
+            |step: &SolvingStep| step.technique.level() == level && config.allows(step.technique);
+        stages
+            .iter()
+            .filter(|&&i| PIPELINE[i].1.iter().any(|&t| t.level() == level))
+            .find_map(|&i| PIPELINE[i].0(board, &accepts))
+    })
+}
+
+/// Applies the first step found by `finders`, in order, until none makes progress.
+fn run_finders(
+    initial_board: &Board,
+    finders: &[TechniqueFinder],
+    accepts: impl Fn(&SolvingStep) -> bool,
+) -> (Vec<SolvingStep>, Board) {
     let mut board = LogicalBoard::from_board(initial_board);
     let mut steps = Vec::new();
 
-    loop {
-        // Try techniques in order of complexity/speed
-        let progress = try_apply_step(&mut board, &mut steps, basic::find_naked_single)
-            || try_apply_step(&mut board, &mut steps, basic::find_hidden_single)
-            || try_apply_step(&mut board, &mut steps, subsets::find_naked_pair)
-            || try_apply_step(&mut board, &mut steps, subsets::find_naked_triple)
-            || try_apply_step(&mut board, &mut steps, intersection::find_pointing_subset)
-            || try_apply_step(&mut board, &mut steps, subsets::find_hidden_pair)
-            || try_apply_step(&mut board, &mut steps, subsets::find_hidden_triple)
-            || try_apply_step(&mut board, &mut steps, intersection::find_claiming_candidates)
-            // Advanced Techniques
-            || try_apply_step(&mut board, &mut steps, fish::find_fish_techniques)
-            || try_apply_step(&mut board, &mut steps, wings::find_xy_wing)
-            || try_apply_step(&mut board, &mut steps, wings::find_xyz_wing)
-            || try_apply_step(&mut board, &mut steps, single_digit::find_skyscraper)
-            || try_apply_step(&mut board, &mut steps, single_digit::find_two_string_kite)
-            // Master Techniques
-            || try_apply_step(&mut board, &mut steps, uniqueness::find_unique_rectangle_type_1)
-            || try_apply_step(&mut board, &mut steps, wings::find_w_wing);
-
-        if !progress {
-            break;
-        }
-    }
+    while finders
+        .iter()
+        .any(|&finder| try_apply_step(&mut board, &mut steps, finder, &accepts))
+    {}
 
     (steps, Board { cells: board.cells })
 }
@@ -238,9 +646,10 @@ pub fn solve_with_steps(initial_board: &Board) -> (Vec<SolvingStep>, Board) {
 fn try_apply_step(
     board: &mut LogicalBoard,
     steps: &mut Vec<SolvingStep>,
-    finder: fn(&LogicalBoard) -> Option<SolvingStep>,
+    finder: TechniqueFinder,
+    accepts: impl Fn(&SolvingStep) -> bool,
 ) -> bool {
-    if let Some(step) = finder(board) {
+    if let Some(step) = finder(board, &accepts) {
         // Apply placements
         for placement in &step.placements {
             board.set_cell(placement.index, placement.value);
@@ -262,19 +671,11 @@ pub fn analyze_difficulty(steps: &[SolvingStep]) -> DifficultyStats {
         intermediate_count: 0,
         advanced_count: 0,
         master_count: 0,
+        extreme_count: 0,
     };
 
     for step in steps {
-        let level = match step.technique.as_str() {
-            "NakedSingle" | "HiddenSingle" => TechniqueLevel::Basic,
-            "PointingPair" | "PointingTriple" | "NakedPair" | "NakedTriple" | "HiddenPair"
-            | "HiddenTriple" | "ClaimingCandidate" => TechniqueLevel::Intermediate,
-            "X-Wing" | "Swordfish" | "XY-Wing" | "XYZ-Wing" | "Skyscraper" | "TwoStringKite" => {
-                TechniqueLevel::Advanced
-            }
-            "Jellyfish" | "UniqueRectangleType1" | "W-Wing" => TechniqueLevel::Master,
-            _ => TechniqueLevel::None,
-        };
+        let level = step.technique.level();
 
         if level > stats.max_level {
             stats.max_level = level;
@@ -284,6 +685,7 @@ pub fn analyze_difficulty(steps: &[SolvingStep]) -> DifficultyStats {
             TechniqueLevel::Intermediate => stats.intermediate_count += 1,
             TechniqueLevel::Advanced => stats.advanced_count += 1,
             TechniqueLevel::Master => stats.master_count += 1,
+            TechniqueLevel::Extreme => stats.extreme_count += 1,
             _ => {}
         }
     }
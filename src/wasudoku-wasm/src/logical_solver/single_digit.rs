@@ -16,21 +16,24 @@
 * along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use super::{LogicalBoard, PEER_MAP, mask_to_vec};
-use crate::types::{CauseCell, Elimination, SolvingStep};
+use super::{BOX_UNITS, LogicalBoard, PEER_MAP, mask_to_vec};
+use crate::types::{CauseCell, Elimination, SolvingStep, Technique};
 
 // --- Skyscraper ---
 
-pub fn find_skyscraper(board: &LogicalBoard) -> Option<SolvingStep> {
+pub fn find_skyscraper(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     let (row_masks, col_masks) = board.get_all_fish_masks();
 
     for num in 1..=9 {
         // Check Rows base
-        if let Some(step) = check_skyscraper(board, num, &row_masks[num], true) {
+        if let Some(step) = check_skyscraper(board, num, &row_masks[num], true, accepts) {
             return Some(step);
         }
         // Check Cols base
-        if let Some(step) = check_skyscraper(board, num, &col_masks[num], false) {
+        if let Some(step) = check_skyscraper(board, num, &col_masks[num], false, accepts) {
             return Some(step);
         }
     }
@@ -43,6 +46,7 @@ fn check_skyscraper(
     num: usize,
     masks: &[u16; 9],
     is_row_base: bool,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
 ) -> Option<SolvingStep> {
     // Find indices of rows/cols with exactly 2 candidates
     let valid_indices: Vec<usize> = masks
@@ -65,7 +69,8 @@ fn check_skyscraper(
                 is_row_base,
                 valid_indices[i],
                 valid_indices[j],
-            ) {
+            ) && accepts(&step)
+            {
                 return Some(step);
             }
         }
@@ -136,25 +141,29 @@ fn check_skyscraper_pair(
 
     if !elims.is_empty() {
         return Some(SolvingStep {
-            technique: "Skyscraper".to_string(),
+            technique: Technique::Skyscraper,
             placements: vec![],
             eliminations: elims,
             cause: vec![
                 CauseCell {
                     index: roof_cell_1,
                     candidates: vec![num as u8],
+                    role: None,
                 },
                 CauseCell {
                     index: roof_cell_2,
                     candidates: vec![num as u8],
+                    role: None,
                 },
                 CauseCell {
                     index: base_cell_1,
                     candidates: vec![num as u8],
+                    role: None,
                 },
                 CauseCell {
                     index: base_cell_2,
                     candidates: vec![num as u8],
+                    role: None,
                 },
             ],
         });
@@ -164,11 +173,16 @@ fn check_skyscraper_pair(
 
 // --- Two-String Kite ---
 
-pub fn find_two_string_kite(board: &LogicalBoard) -> Option<SolvingStep> {
+pub fn find_two_string_kite(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     let (row_masks, col_masks) = board.get_all_fish_masks();
 
     for num in 1..=9 {
-        if let Some(step) = check_two_string_kite_for_num(board, num, &row_masks, &col_masks) {
+        if let Some(step) =
+            check_two_string_kite_for_num(board, num, &row_masks, &col_masks, accepts)
+        {
             return Some(step);
         }
     }
@@ -180,6 +194,7 @@ fn check_two_string_kite_for_num(
     num: usize,
     row_masks: &[[u16; 9]; 10],
     col_masks: &[[u16; 9]; 10],
+    accepts: &dyn Fn(&SolvingStep) -> bool,
 ) -> Option<SolvingStep> {
     let rows_2: Vec<usize> = row_masks[num]
         .iter()
@@ -196,9 +211,14 @@ fn check_two_string_kite_for_num(
 
     for &r in &rows_2 {
         for &c in &cols_2 {
-            if let Some(step) =
-                check_kite_intersection(board, num, r, c, row_masks[num][r], col_masks[num][c])
-            {
+            if let Some(step) = check_kite_intersection(
+                board,
+                num,
+                (r, c),
+                row_masks[num][r],
+                col_masks[num][c],
+                accepts,
+            ) {
                 return Some(step);
             }
         }
@@ -209,10 +229,10 @@ fn check_two_string_kite_for_num(
 fn check_kite_intersection(
     board: &LogicalBoard,
     num: usize,
-    r: usize,
-    c: usize,
+    (r, c): (usize, usize),
     r_mask: u16,
     c_mask: u16,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
 ) -> Option<SolvingStep> {
     let r_cols = mask_to_vec(r_mask);
     let c_rows = mask_to_vec(c_mask);
@@ -223,7 +243,7 @@ fn check_kite_intersection(
     let cell_c1 = (c_rows[0] as usize - 1) * 9 + c;
     let cell_c2 = (c_rows[1] as usize - 1) * 9 + c;
 
-    find_kite_connection(board, num, [cell_r1, cell_r2], [cell_c1, cell_c2])
+    find_kite_connection(board, num, [cell_r1, cell_r2], [cell_c1, cell_c2], accepts)
 }
 
 fn find_kite_connection(
@@ -231,10 +251,13 @@ fn find_kite_connection(
     num: usize,
     row_cells: [usize; 2],
     col_cells: [usize; 2],
+    accepts: &dyn Fn(&SolvingStep) -> bool,
 ) -> Option<SolvingStep> {
     for &rc in &row_cells {
         for &cc in &col_cells {
-            if let Some(step) = check_kite_pair(board, num, rc, cc, row_cells, col_cells) {
+            if let Some(step) = check_kite_pair(board, num, rc, cc, row_cells, col_cells)
+                && accepts(&step)
+            {
                 return Some(step);
             }
         }
@@ -299,28 +322,172 @@ fn construct_kite_step(
 
     if !elims.is_empty() {
         return Some(SolvingStep {
-            technique: "TwoStringKite".to_string(),
+            technique: Technique::TwoStringKite,
             placements: vec![],
             eliminations: elims,
             cause: vec![
                 CauseCell {
                     index: rc,
                     candidates: vec![num as u8],
+                    role: None,
                 },
                 CauseCell {
                     index: cc,
                     candidates: vec![num as u8],
+                    role: None,
                 },
                 CauseCell {
                     index: other_rc,
                     candidates: vec![num as u8],
+                    role: None,
                 },
                 CauseCell {
                     index: other_cc,
                     candidates: vec![num as u8],
+                    role: None,
                 },
             ],
         });
     }
     None
 }
+
+// --- Empty Rectangle ---
+
+/// Searches for an Empty Rectangle: a box whose candidates for a digit all lie on one
+/// row and one column of the box. Combined with a conjugate pair on a line crossing
+/// one arm outside the box, it eliminates the digit where the pair's far end meets the
+/// other arm.
+pub fn find_empty_rectangle(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let (row_masks, col_masks) = board.get_all_fish_masks();
+
+    for num in 1..=9 {
+        let cand_bit = 1 << (num - 1);
+        for (b, box_unit) in BOX_UNITS.iter().enumerate() {
+            let box_cells: Vec<usize> = box_unit
+                .iter()
+                .copied()
+                .filter(|&idx| board.cells[idx] == 0 && (board.candidates[idx] & cand_bit) != 0)
+                .collect();
+            if box_cells.len() < 2 {
+                continue;
+            }
+
+            for r in (b / 3) * 3..(b / 3) * 3 + 3 {
+                for c in (b % 3) * 3..(b % 3) * 3 + 3 {
+                    if !is_empty_rectangle(&box_cells, r, c) {
+                        continue;
+                    }
+                    if let Some(step) = check_empty_rectangle(
+                        board,
+                        num,
+                        &box_cells,
+                        (r, c),
+                        &row_masks[num],
+                        &col_masks[num],
+                        accepts,
+                    ) {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Checks that every box candidate lies on row `r` or column `c`, and that they do not
+/// all sit on a single line (which would be a pointing pair instead).
+#[inline]
+fn is_empty_rectangle(box_cells: &[usize], r: usize, c: usize) -> bool {
+    box_cells.iter().all(|&idx| idx / 9 == r || idx % 9 == c)
+        && !box_cells.iter().all(|&idx| idx / 9 == r)
+        && !box_cells.iter().all(|&idx| idx % 9 == c)
+}
+
+fn check_empty_rectangle(
+    board: &LogicalBoard,
+    num: usize,
+    box_cells: &[usize],
+    (r, c): (usize, usize),
+    row_masks: &[u16; 9],
+    col_masks: &[u16; 9],
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let cand_bit = 1 << (num - 1);
+    let band = r / 3;
+    let stack = c / 3;
+
+    // Conjugate pair in a row outside the band that crosses column `c`.
+    // Its far end in column `far` rules out (r, far).
+    for pair_row in (0..9).filter(|&pr| pr / 3 != band) {
+        let mask = row_masks[pair_row];
+        if mask.count_ones() != 2 || (mask & (1 << c)) == 0 {
+            continue;
+        }
+        let far = (mask & !(1 << c)).trailing_zeros() as usize;
+        let target = r * 9 + far;
+        if far / 3 != stack && (board.candidates[target] & cand_bit) != 0 {
+            let step = construct_empty_rectangle_step(
+                num,
+                box_cells,
+                [pair_row * 9 + c, pair_row * 9 + far],
+                target,
+            );
+            if accepts(&step) {
+                return Some(step);
+            }
+        }
+    }
+
+    // Conjugate pair in a column outside the stack that crosses row `r`.
+    // Its far end in row `far` rules out (far, c).
+    for pair_col in (0..9).filter(|&pc| pc / 3 != stack) {
+        let mask = col_masks[pair_col];
+        if mask.count_ones() != 2 || (mask & (1 << r)) == 0 {
+            continue;
+        }
+        let far = (mask & !(1 << r)).trailing_zeros() as usize;
+        let target = far * 9 + c;
+        if far / 3 != band && (board.candidates[target] & cand_bit) != 0 {
+            let step = construct_empty_rectangle_step(
+                num,
+                box_cells,
+                [r * 9 + pair_col, far * 9 + pair_col],
+                target,
+            );
+            if accepts(&step) {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+fn construct_empty_rectangle_step(
+    num: usize,
+    box_cells: &[usize],
+    strong_link: [usize; 2],
+    target: usize,
+) -> SolvingStep {
+    SolvingStep {
+        technique: Technique::EmptyRectangle,
+        placements: vec![],
+        eliminations: vec![Elimination {
+            index: target,
+            value: num as u8,
+        }],
+        cause: box_cells
+            .iter()
+            .chain(strong_link.iter())
+            .map(|&idx| CauseCell {
+                index: idx,
+                candidates: vec![num as u8],
+                role: None,
+            })
+            .collect(),
+    }
+}
@@ -17,11 +17,14 @@
 */
 
 use super::{ALL_UNITS, LogicalBoard, mask_to_vec};
-use crate::types::{CauseCell, Elimination, SolvingStep};
+use crate::types::{CauseCell, Elimination, SolvingStep, Technique};
 
 // --- Naked Subsets ---
 
-pub fn find_naked_pair(board: &LogicalBoard) -> Option<SolvingStep> {
+pub fn find_naked_pair(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     for unit in ALL_UNITS.iter() {
         // Filter to cells with exactly 2 candidates
         let unit_slice = *unit;
@@ -39,7 +42,8 @@ pub fn find_naked_pair(board: &LogicalBoard) -> Option<SolvingStep> {
                     potential_indices[i],
                     potential_indices[j],
                     unit_slice,
-                ) {
+                ) && accepts(&step)
+                {
                     return Some(step);
                 }
             }
@@ -48,7 +52,10 @@ pub fn find_naked_pair(board: &LogicalBoard) -> Option<SolvingStep> {
     None
 }
 
-pub fn find_naked_triple(board: &LogicalBoard) -> Option<SolvingStep> {
+pub fn find_naked_triple(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     for unit in ALL_UNITS.iter() {
         let unit_slice = *unit;
         // Filter cells with 2 or 3 candidates
@@ -58,7 +65,31 @@ pub fn find_naked_triple(board: &LogicalBoard) -> Option<SolvingStep> {
             continue;
         }
 
-        if let Some(step) = check_naked_triple_combinations(board, &potential_indices, unit_slice) {
+        if let Some(step) =
+            check_naked_triple_combinations(board, &potential_indices, unit_slice, accepts)
+        {
+            return Some(step);
+        }
+    }
+    None
+}
+
+pub fn find_naked_quad(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    for unit in ALL_UNITS.iter() {
+        let unit_slice = *unit;
+        // Filter cells with 2 to 4 candidates
+        let potential_indices = filter_naked_subset_candidates(board, unit_slice, 4);
+
+        if potential_indices.len() < 4 {
+            continue;
+        }
+
+        if let Some(step) =
+            check_naked_quad_combinations(board, &potential_indices, unit_slice, accepts)
+        {
             return Some(step);
         }
     }
@@ -85,7 +116,7 @@ fn check_naked_pair(
 ) -> Option<SolvingStep> {
     let mask = board.candidates[idx1];
     if mask == board.candidates[idx2] && mask.count_ones() == 2 {
-        return construct_naked_subset_step(board, &[idx1, idx2], mask, unit, "NakedPair");
+        return construct_naked_subset_step(board, &[idx1, idx2], mask, unit, Technique::NakedPair);
     }
     None
 }
@@ -95,6 +126,7 @@ fn check_naked_triple_combinations(
     board: &LogicalBoard,
     indices: &[usize],
     unit: &[usize],
+    accepts: &dyn Fn(&SolvingStep) -> bool,
 ) -> Option<SolvingStep> {
     let len = indices.len();
     for i in 0..len {
@@ -102,6 +134,7 @@ fn check_naked_triple_combinations(
             for k in (j + 1)..len {
                 if let Some(step) =
                     check_naked_triple(board, indices[i], indices[j], indices[k], unit)
+                    && accepts(&step)
                 {
                     return Some(step);
                 }
@@ -127,18 +160,53 @@ fn check_naked_triple(
             &[idx1, idx2, idx3],
             union_mask,
             unit,
-            "NakedTriple",
+            Technique::NakedTriple,
         );
     }
     None
 }
 
+#[inline]
+fn check_naked_quad_combinations(
+    board: &LogicalBoard,
+    indices: &[usize],
+    unit: &[usize],
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let len = indices.len();
+    for i in 0..len {
+        for j in (i + 1)..len {
+            for k in (j + 1)..len {
+                for l in (k + 1)..len {
+                    let quad = [indices[i], indices[j], indices[k], indices[l]];
+                    if let Some(step) = check_naked_quad(board, quad, unit)
+                        && accepts(&step)
+                    {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+#[inline]
+fn check_naked_quad(board: &LogicalBoard, quad: [usize; 4], unit: &[usize]) -> Option<SolvingStep> {
+    let union_mask = quad.iter().fold(0, |acc, &idx| acc | board.candidates[idx]);
+
+    if union_mask.count_ones() == 4 {
+        return construct_naked_subset_step(board, &quad, union_mask, unit, Technique::NakedQuad);
+    }
+    None
+}
+
 fn construct_naked_subset_step(
     board: &LogicalBoard,
     indices: &[usize],
     mask: u16,
     unit: &[usize],
-    technique: &str,
+    technique: Technique,
 ) -> Option<SolvingStep> {
     let mut eliminations = Vec::new();
     let cands = mask_to_vec(mask);
@@ -161,7 +229,7 @@ fn construct_naked_subset_step(
     }
 
     Some(SolvingStep {
-        technique: technique.to_string(),
+        technique,
         placements: vec![],
         eliminations,
         cause: indices
@@ -169,6 +237,7 @@ fn construct_naked_subset_step(
             .map(|&i| CauseCell {
                 index: i,
                 candidates: cands.clone(),
+                role: None,
             })
             .collect(),
     })
@@ -176,7 +245,10 @@ fn construct_naked_subset_step(
 
 // --- Hidden Subsets ---
 
-pub fn find_hidden_pair(board: &LogicalBoard) -> Option<SolvingStep> {
+pub fn find_hidden_pair(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     for unit in ALL_UNITS.iter() {
         let unit_slice = *unit;
         let pos_masks = get_candidate_positions_in_unit(board, unit_slice);
@@ -190,6 +262,7 @@ pub fn find_hidden_pair(board: &LogicalBoard) -> Option<SolvingStep> {
             for j in (i + 1)..candidates.len() {
                 if let Some(step) =
                     check_hidden_pair(board, candidates[i], candidates[j], &pos_masks, unit_slice)
+                    && accepts(&step)
                 {
                     return Some(step);
                 }
@@ -199,7 +272,10 @@ pub fn find_hidden_pair(board: &LogicalBoard) -> Option<SolvingStep> {
     None
 }
 
-pub fn find_hidden_triple(board: &LogicalBoard) -> Option<SolvingStep> {
+pub fn find_hidden_triple(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     for unit in ALL_UNITS.iter() {
         let unit_slice = *unit;
         let pos_masks = get_candidate_positions_in_unit(board, unit_slice);
@@ -210,7 +286,29 @@ pub fn find_hidden_triple(board: &LogicalBoard) -> Option<SolvingStep> {
         }
 
         if let Some(step) =
-            check_hidden_triple_combinations(board, &candidates, &pos_masks, unit_slice)
+            check_hidden_triple_combinations(board, &candidates, &pos_masks, unit_slice, accepts)
+        {
+            return Some(step);
+        }
+    }
+    None
+}
+
+pub fn find_hidden_quad(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    for unit in ALL_UNITS.iter() {
+        let unit_slice = *unit;
+        let pos_masks = get_candidate_positions_in_unit(board, unit_slice);
+        let candidates = filter_hidden_subset_candidates(&pos_masks, 4);
+
+        if candidates.len() < 4 {
+            continue;
+        }
+
+        if let Some(step) =
+            check_hidden_quad_combinations(board, &candidates, &pos_masks, unit_slice, accepts)
         {
             return Some(step);
         }
@@ -265,7 +363,7 @@ fn check_hidden_pair(
             &cell_indices,
             keep_mask,
             &[n1 as u8, n2 as u8],
-            "HiddenPair",
+            Technique::HiddenPair,
         );
     }
     None
@@ -277,6 +375,7 @@ fn check_hidden_triple_combinations(
     candidates: &[usize],
     pos_masks: &[u16; 10],
     unit: &[usize],
+    accepts: &dyn Fn(&SolvingStep) -> bool,
 ) -> Option<SolvingStep> {
     let len = candidates.len();
     for i in 0..len {
@@ -289,7 +388,8 @@ fn check_hidden_triple_combinations(
                     candidates[k],
                     pos_masks,
                     unit,
-                ) {
+                ) && accepts(&step)
+                {
                     return Some(step);
                 }
             }
@@ -317,7 +417,57 @@ fn check_hidden_triple(
             &cell_indices,
             keep_mask,
             &[n1 as u8, n2 as u8, n3 as u8],
-            "HiddenTriple",
+            Technique::HiddenTriple,
+        );
+    }
+    None
+}
+
+#[inline]
+fn check_hidden_quad_combinations(
+    board: &LogicalBoard,
+    candidates: &[usize],
+    pos_masks: &[u16; 10],
+    unit: &[usize],
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let len = candidates.len();
+    for i in 0..len {
+        for j in (i + 1)..len {
+            for k in (j + 1)..len {
+                for l in (k + 1)..len {
+                    let nums = [candidates[i], candidates[j], candidates[k], candidates[l]];
+                    if let Some(step) = check_hidden_quad(board, nums, pos_masks, unit)
+                        && accepts(&step)
+                    {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+#[inline]
+fn check_hidden_quad(
+    board: &LogicalBoard,
+    nums: [usize; 4],
+    pos_masks: &[u16; 10],
+    unit: &[usize],
+) -> Option<SolvingStep> {
+    let combined_pos = nums.iter().fold(0, |acc, &n| acc | pos_masks[n]);
+    if combined_pos.count_ones() == 4 {
+        let cell_indices = indices_from_unit_mask(unit, combined_pos);
+        let keep_mask = nums.iter().fold(0, |acc, &n| acc | (1 << (n - 1)));
+        let subset_nums: Vec<u8> = nums.iter().map(|&n| n as u8).collect();
+
+        return construct_hidden_subset_step(
+            board,
+            &cell_indices,
+            keep_mask,
+            &subset_nums,
+            Technique::HiddenQuad,
         );
     }
     None
@@ -339,7 +489,7 @@ fn construct_hidden_subset_step(
     indices: &[usize],
     keep_mask: u16,
     subset_nums: &[u8],
-    technique: &str,
+    technique: Technique,
 ) -> Option<SolvingStep> {
     let mut eliminations = Vec::new();
     for &idx in indices {
@@ -359,7 +509,7 @@ fn construct_hidden_subset_step(
     }
 
     Some(SolvingStep {
-        technique: technique.to_string(),
+        technique,
         placements: vec![],
         eliminations,
         cause: indices
@@ -367,6 +517,7 @@ fn construct_hidden_subset_step(
             .map(|&idx| CauseCell {
                 index: idx,
                 candidates: subset_nums.to_vec(),
+                role: None,
             })
             .collect(),
     })
@@ -0,0 +1,134 @@
+/*
+* Copyright (C) 2025-2026  Henrique Almeida
+* This file is part of WASudoku.
+*
+* WASudoku is free software: you can redistribute it and/or modify
+* it under the terms of the GNU Affero General Public License as published
+* by the Free Software Foundation, either version 3 of the License, or
+* (at your option) any later version.
+*
+* WASudoku is distributed in the hope that it will be useful,
+* but WITHOUT ANY WARRANTY; without even the implied warranty of
+* MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+* GNU Affero General Public License for more details.
+*
+* You should have received a copy of the GNU Affero General Public License
+* along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use super::LogicalBoard;
+use crate::types::{CauseCell, Elimination, Placement, SolvingStep, Technique};
+
+lazy_static::lazy_static! {
+    /// Every way to place one digit nine times on an empty grid, one per row, column
+    /// and box, as cell bitsets. There are 46,656 of them.
+    pub(crate) static ref TEMPLATES: Vec<u128> = {
+        let mut templates = Vec::with_capacity(46_656);
+        build_templates(0, 0, 0, 0, &mut templates);
+        templates
+    };
+}
+
+/// Places the digit in `row` and every row below it, avoiding used columns and boxes.
+fn build_templates(row: usize, cols: u16, boxes: u16, cells: u128, out: &mut Vec<u128>) {
+    if row == 9 {
+        out.push(cells);
+        return;
+    }
+    for col in 0..9 {
+        let b = (row / 3) * 3 + col / 3;
+        if cols & (1 << col) == 0 && boxes & (1 << b) == 0 {
+            build_templates(
+                row + 1,
+                cols | (1 << col),
+                boxes | (1 << b),
+                cells | (1 << (row * 9 + col)),
+                out,
+            );
+        }
+    }
+}
+
+/// Pattern Overlay Method: overlays every template of a digit that fits the board.
+/// Candidates no fitting template covers are eliminated, and cells every fitting
+/// template shares are placed.
+pub fn find_templates(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    for num in 1..=9u8 {
+        let bit = 1 << (num - 1);
+        let mut placed = 0u128;
+        let mut open = 0u128;
+        for idx in 0..81 {
+            if board.cells[idx] == num {
+                placed |= 1 << idx;
+            } else if board.cells[idx] == 0 && board.candidates[idx] & bit != 0 {
+                open |= 1 << idx;
+            }
+        }
+        if open == 0 {
+            continue;
+        }
+
+        let allowed = placed | open;
+        let mut union = 0u128;
+        let mut intersection = u128::MAX;
+        for &template in TEMPLATES.iter() {
+            if template & placed == placed && template & !allowed == 0 {
+                union |= template;
+                intersection &= template;
+            }
+        }
+        // No template fits only on a broken board; leave that to the other techniques.
+        if union == 0 {
+            continue;
+        }
+
+        let to_place = intersection & open;
+        let to_eliminate = open & !union;
+        if to_place != 0 {
+            let step =
+                construct_template_step(Technique::TemplatePlacement, num, open, to_place, 0);
+            if accepts(&step) {
+                return Some(step);
+            }
+        }
+        if to_eliminate != 0 {
+            let step =
+                construct_template_step(Technique::TemplateElimination, num, open, 0, to_eliminate);
+            if accepts(&step) {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+/// The cause lists every open cell of the digit, the cells the templates are laid over.
+fn construct_template_step(
+    technique: Technique,
+    num: u8,
+    open: u128,
+    to_place: u128,
+    to_eliminate: u128,
+) -> SolvingStep {
+    let cells_of = |set: u128| (0..81).filter(move |&idx| set & (1 << idx) != 0);
+
+    SolvingStep {
+        technique,
+        placements: cells_of(to_place)
+            .map(|index| Placement { index, value: num })
+            .collect(),
+        eliminations: cells_of(to_eliminate)
+            .map(|index| Elimination { index, value: num })
+            .collect(),
+        cause: cells_of(open)
+            .map(|index| CauseCell {
+                index,
+                candidates: vec![num],
+                role: None,
+            })
+            .collect(),
+    }
+}
@@ -16,17 +16,138 @@
 * along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use super::{LogicalBoard, mask_to_vec};
-use crate::types::{CauseCell, Elimination, SolvingStep};
+use super::basic::collect_placement_eliminations;
+use super::wings::get_common_peer_eliminations;
+use super::{
+    ALL_CANDIDATES, ALL_UNITS, BOX_UNITS, COL_UNITS, LogicalBoard, PEER_MAP, ROW_UNITS,
+    get_box_index, mask_to_vec,
+};
+use crate::types::{CauseCell, Elimination, Placement, SolvingStep, Technique};
 
 /// Searches for Unique Rectangle Type 1.
-pub fn find_unique_rectangle_type_1(board: &LogicalBoard) -> Option<SolvingStep> {
+/// Three floor cells hold only the UR pair, so the pair is removed from the roof cell.
+pub fn find_unique_rectangle_type_1(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    find_ur_pattern(board, check_ur_type_1, accepts)
+}
+
+#[inline]
+fn is_valid_ur_geometry(indices: &[usize; 4]) -> bool {
+    let b_tl = get_box_index(indices[0]);
+    let b_tr = get_box_index(indices[1]);
+    let b_bl = get_box_index(indices[2]);
+    let b_br = get_box_index(indices[3]);
+
+    // Case 1: Vertical sharing (Floor/Ceiling in different boxes)
+    if b_tl == b_bl && b_tr == b_br && b_tl != b_tr {
+        return true;
+    }
+    // Case 2: Horizontal sharing (Walls in different boxes)
+    if b_tl == b_tr && b_bl == b_br && b_tl != b_bl {
+        return true;
+    }
+
+    false
+}
+
+// --- Unique Rectangle Types 1-6 and Hidden Rectangle ---
+//
+// The four UR cells are always ordered [top-left, top-right, bottom-left, bottom-right],
+// so positions `p` and `3 - p` are diagonal and every other pair shares a row or column.
+// "Floor" cells hold exactly the UR pair, "roof" cells hold the pair plus extra candidates.
+
+/// Checks a rectangle for a specific UR type, given the candidate pair mask `ab`,
+/// returning the first step `accepts` takes.
+type UrCheck =
+    fn(&LogicalBoard, &[usize; 4], u16, &dyn Fn(&SolvingStep) -> bool) -> Option<SolvingStep>;
+
+/// Searches for Unique Rectangle Type 2.
+/// Two roof cells in a line share a single extra candidate, which must be in one of them.
+pub fn find_unique_rectangle_type_2(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    find_ur_pattern(board, check_ur_type_2, accepts)
+}
+
+/// Searches for Unique Rectangle Type 3.
+/// The extra candidates of two roof cells act as one virtual cell in a naked subset.
+pub fn find_unique_rectangle_type_3(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    find_ur_pattern(board, check_ur_type_3, accepts)
+}
+
+/// Searches for Unique Rectangle Type 4.
+/// One UR digit is confined to the roof cells in a shared unit, so the other UR digit is removed.
+pub fn find_unique_rectangle_type_4(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    find_ur_pattern(board, check_ur_type_4, accepts)
+}
+
+/// Searches for Unique Rectangle Type 5.
+/// Two diagonal or three cells share a single extra candidate, which must be in one of them.
+pub fn find_unique_rectangle_type_5(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    find_ur_pattern(board, check_ur_type_5, accepts)
+}
+
+/// Searches for Unique Rectangle Type 6.
+/// One UR digit forms an X-Wing on the rectangle, so it is removed from the diagonal roof cells.
+pub fn find_unique_rectangle_type_6(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    find_ur_pattern(board, check_ur_type_6, accepts)
+}
+
+/// Searches for Hidden Unique Rectangles.
+/// A bivalue corner plus strong links on one UR digit through the opposite corner
+/// remove the other UR digit from that opposite corner.
+pub fn find_hidden_unique_rectangle(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    find_ur_pattern(board, check_hidden_ur, accepts)
+}
+
+/// Enumerates every valid rectangle of empty cells and every candidate pair common to it.
+fn find_ur_pattern(
+    board: &LogicalBoard,
+    check: UrCheck,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     for r1 in 0..9 {
         for r2 in (r1 + 1)..9 {
             for c1 in 0..9 {
                 for c2 in (c1 + 1)..9 {
-                    if let Some(step) = check_ur_for_coords(board, r1, r2, c1, c2) {
-                        return Some(step);
+                    let indices = [r1 * 9 + c1, r1 * 9 + c2, r2 * 9 + c1, r2 * 9 + c2];
+
+                    if !is_valid_ur_geometry(&indices)
+                        || indices.iter().any(|&i| board.cells[i] != 0)
+                    {
+                        continue;
+                    }
+
+                    let common = indices
+                        .iter()
+                        .fold(ALL_CANDIDATES, |acc, &i| acc & board.candidates[i]);
+                    let digits = mask_to_vec(common);
+
+                    for i in 0..digits.len() {
+                        for j in (i + 1)..digits.len() {
+                            let ab = (1 << (digits[i] - 1)) | (1 << (digits[j] - 1));
+                            if let Some(step) = check(board, &indices, ab, accepts) {
+                                return Some(step);
+                            }
+                        }
                     }
                 }
             }
@@ -35,110 +156,540 @@ pub fn find_unique_rectangle_type_1(board: &LogicalBoard) -> Option<SolvingStep>
     None
 }
 
+/// Splits the rectangle positions into floor (exactly the UR pair) and roof (extras) cells.
 #[inline]
-fn check_ur_for_coords(
+fn split_floor_roof(
     board: &LogicalBoard,
-    r1: usize,
-    r2: usize,
-    c1: usize,
-    c2: usize,
-) -> Option<SolvingStep> {
-    let idx_tl = r1 * 9 + c1;
-    let idx_tr = r1 * 9 + c2;
-    let idx_bl = r2 * 9 + c1;
-    let idx_br = r2 * 9 + c2;
+    indices: &[usize; 4],
+    ab: u16,
+) -> (Vec<usize>, Vec<usize>) {
+    (0..4).partition(|&p| board.candidates[indices[p]] == ab)
+}
 
-    let indices = [idx_tl, idx_tr, idx_bl, idx_br];
+/// Returns the single extra candidate shared by all given positions, if there is one.
+#[inline]
+fn single_shared_extra(
+    board: &LogicalBoard,
+    indices: &[usize; 4],
+    positions: &[usize],
+    ab: u16,
+) -> Option<u16> {
+    let extra = board.candidates[indices[positions[0]]] & !ab;
+    let all_same = positions
+        .iter()
+        .all(|&p| board.candidates[indices[p]] & !ab == extra);
+    if extra.count_ones() == 1 && all_same {
+        Some(extra)
+    } else {
+        None
+    }
+}
 
-    if !is_valid_ur_geometry(&indices) {
-        return None;
+/// Returns the units (line and possibly box) shared by two aligned rectangle cells.
+#[inline]
+fn shared_units(idx1: usize, idx2: usize) -> Vec<&'static [usize; 9]> {
+    let mut units = Vec::with_capacity(2);
+    if idx1 / 9 == idx2 / 9 {
+        units.push(&ROW_UNITS[idx1 / 9]);
+    } else {
+        units.push(&COL_UNITS[idx1 % 9]);
+    }
+    if get_box_index(idx1) == get_box_index(idx2) {
+        units.push(&BOX_UNITS[get_box_index(idx1)]);
     }
+    units
+}
+
+/// Builds the cause list: floor cells show the UR pair, roof cells show all their candidates.
+fn build_ur_cause(board: &LogicalBoard, indices: &[usize; 4], ab: u16) -> Vec<CauseCell> {
+    indices
+        .iter()
+        .map(|&i| CauseCell {
+            index: i,
+            candidates: if board.candidates[i] == ab {
+                mask_to_vec(ab)
+            } else {
+                mask_to_vec(board.candidates[i])
+            },
+            role: None,
+        })
+        .collect()
+}
 
-    // All cells must be empty
-    if indices.iter().any(|&i| board.cells[i] != 0) {
+fn check_ur_type_1(
+    board: &LogicalBoard,
+    indices: &[usize; 4],
+    ab: u16,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let (floor, roof) = split_floor_roof(board, indices, ab);
+    if floor.len() != 3 {
         return None;
     }
 
-    solve_ur_type_1(board, &indices)
+    // The cause is the three floor cells, each with only the deadly pair.
+    let eliminations = mask_to_vec(ab)
+        .into_iter()
+        .map(|value| Elimination {
+            index: indices[roof[0]],
+            value,
+        })
+        .collect();
+    let cause = floor
+        .iter()
+        .map(|&p| CauseCell {
+            index: indices[p],
+            candidates: mask_to_vec(ab),
+            role: None,
+        })
+        .collect();
+
+    Some(SolvingStep {
+        technique: Technique::UniqueRectangleType1,
+        placements: vec![],
+        eliminations,
+        cause,
+    })
+    .filter(|step| accepts(step))
 }
 
-#[inline]
-fn is_valid_ur_geometry(indices: &[usize; 4]) -> bool {
-    let b_tl = get_box_index(indices[0]);
-    let b_tr = get_box_index(indices[1]);
-    let b_bl = get_box_index(indices[2]);
-    let b_br = get_box_index(indices[3]);
+fn check_ur_type_2(
+    board: &LogicalBoard,
+    indices: &[usize; 4],
+    ab: u16,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let (floor, roof) = split_floor_roof(board, indices, ab);
+    if floor.len() != 2 || roof[0] + roof[1] == 3 {
+        return None;
+    }
 
-    // Case 1: Vertical sharing (Floor/Ceiling in different boxes)
-    if b_tl == b_bl && b_tr == b_br && b_tl != b_tr {
-        return true;
+    let extra = single_shared_extra(board, indices, &roof, ab)?;
+    let extra_val = (extra.trailing_zeros() + 1) as u8;
+    let elims = get_common_peer_eliminations(board, indices[roof[0]], indices[roof[1]], extra_val);
+
+    build_ur_step(
+        board,
+        indices,
+        ab,
+        elims,
+        Technique::UniqueRectangleType2,
+        vec![],
+        accepts,
+    )
+}
+
+fn check_ur_type_3(
+    board: &LogicalBoard,
+    indices: &[usize; 4],
+    ab: u16,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let (floor, roof) = split_floor_roof(board, indices, ab);
+    if floor.len() != 2 || roof[0] + roof[1] == 3 {
+        return None;
     }
-    // Case 2: Horizontal sharing (Walls in different boxes)
-    if b_tl == b_tr && b_bl == b_br && b_tl != b_bl {
-        return true;
+
+    let (roof1, roof2) = (indices[roof[0]], indices[roof[1]]);
+    let extras = (board.candidates[roof1] | board.candidates[roof2]) & !ab;
+
+    for unit in shared_units(roof1, roof2) {
+        let others: Vec<usize> = unit
+            .iter()
+            .cloned()
+            .filter(|&i| i != roof1 && i != roof2 && board.cells[i] == 0)
+            .collect();
+
+        for size in 1..=3 {
+            if let Some(step) =
+                find_ur_type_3_subset(board, indices, ab, extras, unit, &others, size, accepts)
+            {
+                return Some(step);
+            }
+        }
     }
+    None
+}
 
-    false
+/// Looks for `size` cells in the unit which, together with the roof's extra candidates,
+/// form a naked subset of `size + 1` digits.
+#[allow(clippy::too_many_arguments)]
+fn find_ur_type_3_subset(
+    board: &LogicalBoard,
+    indices: &[usize; 4],
+    ab: u16,
+    extras: u16,
+    unit: &[usize; 9],
+    others: &[usize],
+    size: usize,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let mut combo = Vec::with_capacity(size);
+    find_ur_type_3_combo(
+        board, indices, ab, extras, unit, others, size, 0, &mut combo, accepts,
+    )
 }
 
-#[inline]
-fn get_box_index(idx: usize) -> usize {
-    (idx / 27) * 3 + (idx % 9) / 3
+#[allow(clippy::too_many_arguments)]
+fn find_ur_type_3_combo(
+    board: &LogicalBoard,
+    indices: &[usize; 4],
+    ab: u16,
+    extras: u16,
+    unit: &[usize; 9],
+    others: &[usize],
+    size: usize,
+    start: usize,
+    combo: &mut Vec<usize>,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    if combo.len() == size {
+        let subset_mask = combo
+            .iter()
+            .fold(extras, |acc, &i| acc | board.candidates[i]);
+        if subset_mask.count_ones() as usize != size + 1 {
+            return None;
+        }
+
+        let mut elims = Vec::new();
+        for &idx in unit {
+            if indices.contains(&idx) || combo.contains(&idx) || board.cells[idx] != 0 {
+                continue;
+            }
+            for val in mask_to_vec(board.candidates[idx] & subset_mask) {
+                elims.push(Elimination {
+                    index: idx,
+                    value: val,
+                });
+            }
+        }
+
+        let subset_cause = combo
+            .iter()
+            .map(|&i| CauseCell {
+                index: i,
+                candidates: mask_to_vec(board.candidates[i]),
+                role: None,
+            })
+            .collect();
+        return build_ur_step(
+            board,
+            indices,
+            ab,
+            elims,
+            Technique::UniqueRectangleType3,
+            subset_cause,
+            accepts,
+        );
+    }
+
+    for i in start..others.len() {
+        combo.push(others[i]);
+        if let Some(step) = find_ur_type_3_combo(
+            board,
+            indices,
+            ab,
+            extras,
+            unit,
+            others,
+            size,
+            i + 1,
+            combo,
+            accepts,
+        ) {
+            return Some(step);
+        }
+        combo.pop();
+    }
+    None
 }
 
-fn solve_ur_type_1(board: &LogicalBoard, indices: &[usize; 4]) -> Option<SolvingStep> {
-    let masks: Vec<u16> = indices.iter().map(|&i| board.candidates[i]).collect();
+fn check_ur_type_4(
+    board: &LogicalBoard,
+    indices: &[usize; 4],
+    ab: u16,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let (floor, roof) = split_floor_roof(board, indices, ab);
+    if floor.len() != 2 || roof[0] + roof[1] == 3 {
+        return None;
+    }
 
-    // Check if we have at least 3 bivalue cells with the same mask
-    let mut common_mask = 0;
-    let mut bivalue_count = 0;
+    let (roof1, roof2) = (indices[roof[0]], indices[roof[1]]);
+    for unit in shared_units(roof1, roof2) {
+        for strong_val in mask_to_vec(ab) {
+            let strong_bit = 1 << (strong_val - 1);
+            let confined = unit.iter().all(|&i| {
+                i == roof1
+                    || i == roof2
+                    || board.cells[i] != 0
+                    || board.candidates[i] & strong_bit == 0
+            });
 
-    for i in 0..4 {
-        if masks[i].count_ones() == 2 {
-            let count = masks.iter().filter(|&&m| m == masks[i]).count();
-            if count >= 3 {
-                bivalue_count = count;
-                common_mask = masks[i];
-                break;
+            if confined {
+                // The roof must hold the strong digit, so the other UR digit would be deadly.
+                let elim_val = (ab & !strong_bit).trailing_zeros() as u8 + 1;
+                let elims = vec![
+                    Elimination {
+                        index: roof1,
+                        value: elim_val,
+                    },
+                    Elimination {
+                        index: roof2,
+                        value: elim_val,
+                    },
+                ];
+                if let Some(step) = build_ur_step(
+                    board,
+                    indices,
+                    ab,
+                    elims,
+                    Technique::UniqueRectangleType4,
+                    vec![],
+                    accepts,
+                ) {
+                    return Some(step);
+                }
             }
         }
     }
+    None
+}
 
-    if bivalue_count < 3 {
+fn check_ur_type_5(
+    board: &LogicalBoard,
+    indices: &[usize; 4],
+    ab: u16,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let (_, roof) = split_floor_roof(board, indices, ab);
+    let diagonal_pair = roof.len() == 2 && roof[0] + roof[1] == 3;
+    if !diagonal_pair && roof.len() != 3 {
         return None;
     }
 
-    // Find the "target" cell
-    for i in 0..4 {
-        let mask = masks[i];
-        if mask != common_mask && (mask & common_mask) == common_mask {
-            let elim_vals = mask_to_vec(common_mask);
-            let eliminations: Vec<Elimination> = elim_vals
-                .iter()
-                .map(|&val| Elimination {
-                    index: indices[i],
-                    value: val,
+    let extra = single_shared_extra(board, indices, &roof, ab)?;
+    let extra_val = (extra.trailing_zeros() + 1) as u8;
+    let roof_cells: Vec<usize> = roof.iter().map(|&p| indices[p]).collect();
+
+    let elims: Vec<Elimination> = PEER_MAP[roof_cells[0]]
+        .iter()
+        .filter(|&&peer| {
+            board.cells[peer] == 0
+                && board.candidates[peer] & extra != 0
+                && roof_cells[1..].iter().all(|&r| PEER_MAP[r].contains(&peer))
+        })
+        .map(|&peer| Elimination {
+            index: peer,
+            value: extra_val,
+        })
+        .collect();
+
+    build_ur_step(
+        board,
+        indices,
+        ab,
+        elims,
+        Technique::UniqueRectangleType5,
+        vec![],
+        accepts,
+    )
+}
+
+fn check_ur_type_6(
+    board: &LogicalBoard,
+    indices: &[usize; 4],
+    ab: u16,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let (floor, roof) = split_floor_roof(board, indices, ab);
+    if floor.len() != 2 || floor[0] + floor[1] != 3 {
+        return None;
+    }
+
+    for x_val in mask_to_vec(ab) {
+        let x_bit = 1 << (x_val - 1);
+        // X is confined to the rectangle in both rows or in both columns.
+        let confined = |lines: [&[usize; 9]; 2]| {
+            lines.iter().all(|line| {
+                line.iter().all(|&i| {
+                    indices.contains(&i) || board.cells[i] != 0 || board.candidates[i] & x_bit == 0
                 })
-                .collect();
+            })
+        };
+        let x_wing = confined([&ROW_UNITS[indices[0] / 9], &ROW_UNITS[indices[3] / 9]])
+            || confined([&COL_UNITS[indices[0] % 9], &COL_UNITS[indices[3] % 9]]);
 
-            let cause: Vec<CauseCell> = indices
+        if x_wing {
+            // The X-Wing must use a diagonal; the roof diagonal would complete the deadly pattern.
+            let elims = roof
                 .iter()
-                .enumerate()
-                .filter(|&(idx, _)| idx != i)
-                .map(|(_, &cell_idx)| CauseCell {
-                    index: cell_idx,
-                    candidates: elim_vals.clone(),
+                .map(|&p| Elimination {
+                    index: indices[p],
+                    value: x_val,
                 })
                 .collect();
+            if let Some(step) = build_ur_step(
+                board,
+                indices,
+                ab,
+                elims,
+                Technique::UniqueRectangleType6,
+                vec![],
+                accepts,
+            ) {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
 
-            return Some(SolvingStep {
-                technique: "UniqueRectangleType1".to_string(),
-                placements: vec![],
-                eliminations,
-                cause,
-            });
+fn check_hidden_ur(
+    board: &LogicalBoard,
+    indices: &[usize; 4],
+    ab: u16,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    for corner in 0..4 {
+        if board.candidates[indices[corner]] != ab {
+            continue;
+        }
+        let target_pos = 3 - corner;
+        let target = indices[target_pos];
+        let row_mate = indices[target_pos ^ 1];
+        let col_mate = indices[target_pos ^ 2];
+
+        for x_val in mask_to_vec(ab) {
+            let x_bit = 1 << (x_val - 1);
+            let only_in = |unit: &[usize; 9], mate: usize| {
+                unit.iter().all(|&i| {
+                    i == target
+                        || i == mate
+                        || board.cells[i] != 0
+                        || board.candidates[i] & x_bit == 0
+                })
+            };
+
+            if only_in(&ROW_UNITS[target / 9], row_mate)
+                && only_in(&COL_UNITS[target % 9], col_mate)
+            {
+                // If the target were the other UR digit, both strong links would complete
+                // the deadly pattern around the bivalue corner.
+                let elim_val = (ab & !x_bit).trailing_zeros() as u8 + 1;
+                let elims = vec![Elimination {
+                    index: target,
+                    value: elim_val,
+                }];
+                if let Some(step) = build_ur_step(
+                    board,
+                    indices,
+                    ab,
+                    elims,
+                    Technique::HiddenUniqueRectangle,
+                    vec![],
+                    accepts,
+                ) {
+                    return Some(step);
+                }
+            }
         }
     }
+    None
+}
 
+#[inline]
+fn build_ur_step(
+    board: &LogicalBoard,
+    indices: &[usize; 4],
+    ab: u16,
+    eliminations: Vec<Elimination>,
+    technique: Technique,
+    extra_cause: Vec<CauseCell>,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    if eliminations.is_empty() {
+        return None;
+    }
+
+    let mut cause = build_ur_cause(board, indices, ab);
+    cause.extend(extra_cause);
+
+    Some(SolvingStep {
+        technique,
+        placements: vec![],
+        eliminations,
+        cause,
+    })
+    .filter(|step| accepts(step))
+}
+
+// --- BUG+1 ---
+
+/// Searches for a Bivalue Universal Grave plus one.
+/// If every unsolved cell is bivalue except one trivalue cell, and each candidate
+/// appears exactly twice per unit apart from one digit of that cell, the position
+/// would have multiple solutions unless the trivalue cell takes that digit.
+pub fn find_bug_plus_one(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    let mut trivalue_idx = None;
+
+    for i in 0..81 {
+        if board.cells[i] != 0 {
+            continue;
+        }
+        match board.candidates[i].count_ones() {
+            2 => {}
+            3 if trivalue_idx.is_none() => trivalue_idx = Some(i),
+            _ => return None,
+        }
+    }
+
+    let target = trivalue_idx?;
+    for value in mask_to_vec(board.candidates[target]) {
+        if is_bug_without(board, target, value) {
+            let step = SolvingStep {
+                technique: Technique::BugPlusOne,
+                placements: vec![Placement {
+                    index: target,
+                    value,
+                }],
+                eliminations: collect_placement_eliminations(board, target, value),
+                cause: vec![CauseCell {
+                    index: target,
+                    candidates: mask_to_vec(board.candidates[target]),
+                    role: None,
+                }],
+            };
+            if accepts(&step) {
+                return Some(step);
+            }
+        }
+    }
     None
 }
+
+/// Checks if removing `value` from the trivalue cell leaves every candidate exactly
+/// twice (or not at all) in every unit.
+fn is_bug_without(board: &LogicalBoard, target: usize, value: u8) -> bool {
+    let removed_bit = 1 << (value - 1);
+
+    ALL_UNITS.iter().all(|unit| {
+        let mut counts = [0u8; 9];
+        for &idx in unit.iter() {
+            if board.cells[idx] != 0 {
+                continue;
+            }
+            let mut mask = board.candidates[idx];
+            if idx == target {
+                mask &= !removed_bit;
+            }
+            for cand in mask_to_vec(mask) {
+                counts[cand as usize - 1] += 1;
+            }
+        }
+        counts.iter().all(|&c| c == 0 || c == 2)
+    })
+}
@@ -16,12 +16,15 @@
 * along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use super::{ALL_UNITS, LogicalBoard, PEER_MAP, mask_to_vec};
-use crate::types::{CauseCell, Elimination, SolvingStep};
+use super::{ALL_UNITS, BOX_UNITS, COL_UNITS, LogicalBoard, PEER_MAP, ROW_UNITS, mask_to_vec};
+use crate::types::{CauseCell, Elimination, SolvingStep, Technique};
 
 // --- XY-Wing ---
 
-pub fn find_xy_wing(board: &LogicalBoard) -> Option<SolvingStep> {
+pub fn find_xy_wing(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     // Collect bi-value cells
     let bivalue_cells: Vec<usize> = (0..81)
         .filter(|&i| board.cells[i] == 0 && board.candidates[i].count_ones() == 2)
@@ -32,14 +35,18 @@ pub fn find_xy_wing(board: &LogicalBoard) -> Option<SolvingStep> {
     }
 
     for &pivot_idx in &bivalue_cells {
-        if let Some(step) = find_xy_wing_for_pivot(board, pivot_idx) {
+        if let Some(step) = find_xy_wing_for_pivot(board, pivot_idx, accepts) {
             return Some(step);
         }
     }
     None
 }
 
-fn find_xy_wing_for_pivot(board: &LogicalBoard, pivot_idx: usize) -> Option<SolvingStep> {
+fn find_xy_wing_for_pivot(
+    board: &LogicalBoard,
+    pivot_idx: usize,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     let pivot_cands = mask_to_vec(board.candidates[pivot_idx]);
     let a = pivot_cands[0];
     let b = pivot_cands[1];
@@ -56,7 +63,9 @@ fn find_xy_wing_for_pivot(board: &LogicalBoard, pivot_idx: usize) -> Option<Solv
         .collect();
 
     for &p1_idx in &peer_bivalues {
-        if let Some(step) = check_xy_wing_pincers(board, pivot_idx, p1_idx, &peer_bivalues, a, b) {
+        if let Some(step) =
+            check_xy_wing_pincers(board, pivot_idx, p1_idx, &peer_bivalues, (a, b), accepts)
+        {
             return Some(step);
         }
     }
@@ -68,8 +77,8 @@ fn check_xy_wing_pincers(
     pivot_idx: usize,
     p1_idx: usize,
     peers: &[usize],
-    a: u8,
-    b: u8,
+    (a, b): (u8, u8),
+    accepts: &dyn Fn(&SolvingStep) -> bool,
 ) -> Option<SolvingStep> {
     let p1_cands = board.candidates[p1_idx];
     let share_a = (p1_cands & (1 << (a - 1))) != 0;
@@ -100,26 +109,33 @@ fn check_xy_wing_pincers(
             // Found a potential XY-Wing. Eliminate C from cells seen by BOTH P1 and P2
             let elims = find_xy_wing_eliminations(board, p1_idx, p2_idx, pivot_idx, c_val);
 
-            if !elims.is_empty() {
-                return Some(SolvingStep {
-                    technique: "XY-Wing".to_string(),
-                    placements: vec![],
-                    eliminations: elims,
-                    cause: vec![
-                        CauseCell {
-                            index: pivot_idx,
-                            candidates: vec![a, b],
-                        },
-                        CauseCell {
-                            index: p1_idx,
-                            candidates: mask_to_vec(board.candidates[p1_idx]),
-                        },
-                        CauseCell {
-                            index: p2_idx,
-                            candidates: mask_to_vec(board.candidates[p2_idx]),
-                        },
-                    ],
-                });
+            if elims.is_empty() {
+                continue;
+            }
+            let step = SolvingStep {
+                technique: Technique::XYWing,
+                placements: vec![],
+                eliminations: elims,
+                cause: vec![
+                    CauseCell {
+                        index: pivot_idx,
+                        candidates: vec![a, b],
+                        role: None,
+                    },
+                    CauseCell {
+                        index: p1_idx,
+                        candidates: mask_to_vec(board.candidates[p1_idx]),
+                        role: None,
+                    },
+                    CauseCell {
+                        index: p2_idx,
+                        candidates: mask_to_vec(board.candidates[p2_idx]),
+                        role: None,
+                    },
+                ],
+            };
+            if accepts(&step) {
+                return Some(step);
             }
         }
     }
@@ -154,21 +170,28 @@ fn find_xy_wing_eliminations(
 
 // --- XYZ-Wing ---
 
-pub fn find_xyz_wing(board: &LogicalBoard) -> Option<SolvingStep> {
+pub fn find_xyz_wing(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     // Pivot must have 3 candidates
     let trivalue_cells: Vec<usize> = (0..81)
         .filter(|&i| board.cells[i] == 0 && board.candidates[i].count_ones() == 3)
         .collect();
 
     for &pivot_idx in &trivalue_cells {
-        if let Some(step) = find_xyz_wing_for_pivot(board, pivot_idx) {
+        if let Some(step) = find_xyz_wing_for_pivot(board, pivot_idx, accepts) {
             return Some(step);
         }
     }
     None
 }
 
-fn find_xyz_wing_for_pivot(board: &LogicalBoard, pivot_idx: usize) -> Option<SolvingStep> {
+fn find_xyz_wing_for_pivot(
+    board: &LogicalBoard,
+    pivot_idx: usize,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     let pivot_mask = board.candidates[pivot_idx];
 
     // Find potential pincers: bivalue cells that are subsets of the pivot
@@ -194,7 +217,8 @@ fn find_xyz_wing_for_pivot(board: &LogicalBoard, pivot_idx: usize) -> Option<Sol
                 potential_pincers[i],
                 potential_pincers[j],
                 pivot_mask,
-            ) {
+            ) && accepts(&step)
+            {
                 return Some(step);
             }
         }
@@ -241,21 +265,24 @@ fn check_xyz_wing_pincers(
 
     if !elims.is_empty() {
         return Some(SolvingStep {
-            technique: "XYZ-Wing".to_string(),
+            technique: Technique::XYZWing,
             placements: vec![],
             eliminations: elims,
             cause: vec![
                 CauseCell {
                     index: pivot,
                     candidates: mask_to_vec(pivot_mask),
+                    role: None,
                 },
                 CauseCell {
                     index: p1,
                     candidates: mask_to_vec(m1),
+                    role: None,
                 },
                 CauseCell {
                     index: p2,
                     candidates: mask_to_vec(m2),
+                    role: None,
                 },
             ],
         });
@@ -263,14 +290,177 @@ fn check_xyz_wing_pincers(
     None
 }
 
+// --- WXYZ-Wing / Bent Naked Subsets ---
+
+/// Largest bent naked subset searched, the five cells of a VWXYZ-Wing.
+const MAX_BENT_SUBSET_SIZE: usize = 5;
+
+/// Searches for bent naked subsets of three to five cells, the whole wing family from
+/// XY-Wing to VWXYZ-Wing, in one pass. Each step is named after the size it found.
+/// N cells in a box and a crossing line hold exactly N candidates. Every digit whose
+/// cells all see each other appears at most once; if only one digit Z is not restricted
+/// that way, Z must be in the subset and is eliminated from cells seeing all its cells.
+pub fn find_bent_naked_subset(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    for (box_idx, box_unit) in BOX_UNITS.iter().enumerate() {
+        for k in 0..3 {
+            let row = &ROW_UNITS[(box_idx / 3) * 3 + k];
+            let col = &COL_UNITS[(box_idx % 3) * 3 + k];
+            for line in [row, col] {
+                let pool: Vec<usize> = box_unit
+                    .iter()
+                    .chain(line.iter().filter(|idx| !box_unit.contains(idx)))
+                    .cloned()
+                    .filter(|&idx| {
+                        let c = board.candidates[idx].count_ones() as usize;
+                        board.cells[idx] == 0 && (2..=MAX_BENT_SUBSET_SIZE).contains(&c)
+                    })
+                    .collect();
+                let mut combo = Vec::with_capacity(MAX_BENT_SUBSET_SIZE);
+                if let Some(step) =
+                    find_bent_combo(board, box_unit, line, &pool, 0, &mut combo, 0, accepts)
+                {
+                    return Some(step);
+                }
+            }
+        }
+    }
+    None
+}
+
+#[allow(clippy::too_many_arguments)]
+fn find_bent_combo(
+    board: &LogicalBoard,
+    box_unit: &[usize; 9],
+    line: &[usize; 9],
+    pool: &[usize],
+    start: usize,
+    combo: &mut Vec<usize>,
+    union_mask: u16,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
+    if combo.len() >= 3 && union_mask.count_ones() as usize == combo.len() {
+        // A subset within one unit is a plain naked subset.
+        let bent = combo.iter().any(|idx| !line.contains(idx))
+            && combo.iter().any(|idx| !box_unit.contains(idx));
+        if bent
+            && let Some(step) =
+                construct_bent_subset_step(board, combo, union_mask).filter(|step| accepts(step))
+        {
+            return Some(step);
+        }
+    }
+    if combo.len() == MAX_BENT_SUBSET_SIZE {
+        return None;
+    }
+
+    for i in start..pool.len() {
+        let next_mask = union_mask | board.candidates[pool[i]];
+        if next_mask.count_ones() as usize > MAX_BENT_SUBSET_SIZE {
+            continue;
+        }
+        combo.push(pool[i]);
+        if let Some(step) = find_bent_combo(
+            board,
+            box_unit,
+            line,
+            pool,
+            i + 1,
+            combo,
+            next_mask,
+            accepts,
+        ) {
+            return Some(step);
+        }
+        combo.pop();
+    }
+    None
+}
+
+fn construct_bent_subset_step(
+    board: &LogicalBoard,
+    cells: &[usize],
+    union_mask: u16,
+) -> Option<SolvingStep> {
+    let technique = match cells.len() {
+        3 if cells
+            .iter()
+            .all(|&idx| board.candidates[idx].count_ones() == 2) =>
+        {
+            Technique::XYWing
+        }
+        3 => Technique::XYZWing,
+        4 => Technique::WXYZWing,
+        _ => Technique::VWXYZWing,
+    };
+
+    let digit_cells = |val: u8| -> Vec<usize> {
+        cells
+            .iter()
+            .cloned()
+            .filter(|&idx| board.candidates[idx] & (1 << (val - 1)) != 0)
+            .collect()
+    };
+    let unrestricted: Vec<u8> = mask_to_vec(union_mask)
+        .into_iter()
+        .filter(|&val| {
+            let positions = digit_cells(val);
+            positions
+                .iter()
+                .enumerate()
+                .any(|(i, &a)| positions[i + 1..].iter().any(|&b| !are_peers(a, b)))
+        })
+        .collect();
+
+    // With every digit restricted the cells form a locked set and all digits qualify.
+    let targets = match unrestricted.len() {
+        0 => mask_to_vec(union_mask),
+        1 => unrestricted,
+        _ => return None,
+    };
+
+    let mut elims = Vec::new();
+    for val in targets {
+        elims.extend(get_common_peer_eliminations_of(
+            board,
+            &digit_cells(val),
+            val,
+        ));
+    }
+    if elims.is_empty() {
+        return None;
+    }
+
+    Some(SolvingStep {
+        technique,
+        placements: vec![],
+        eliminations: elims,
+        cause: cells
+            .iter()
+            .map(|&idx| CauseCell {
+                index: idx,
+                candidates: mask_to_vec(board.candidates[idx]),
+                role: None,
+            })
+            .collect(),
+    })
+}
+
 // --- W-Wing ---
 
-pub fn find_w_wing(board: &LogicalBoard) -> Option<SolvingStep> {
+pub fn find_w_wing(
+    board: &LogicalBoard,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
+) -> Option<SolvingStep> {
     let bivalue_cells = get_bivalue_cells(board);
 
     for i in 0..bivalue_cells.len() {
         for j in (i + 1)..bivalue_cells.len() {
-            if let Some(step) = check_w_wing_pair(board, &bivalue_cells[i], &bivalue_cells[j]) {
+            if let Some(step) =
+                check_w_wing_pair(board, &bivalue_cells[i], &bivalue_cells[j], accepts)
+            {
                 return Some(step);
             }
         }
@@ -279,7 +469,7 @@ pub fn find_w_wing(board: &LogicalBoard) -> Option<SolvingStep> {
 }
 
 #[inline]
-fn get_bivalue_cells(board: &LogicalBoard) -> Vec<(usize, u16)> {
+pub(super) fn get_bivalue_cells(board: &LogicalBoard) -> Vec<(usize, u16)> {
     (0..81)
         .filter(|&i| board.cells[i] == 0 && board.candidates[i].count_ones() == 2)
         .map(|i| (i, board.candidates[i]))
@@ -291,6 +481,7 @@ fn check_w_wing_pair(
     board: &LogicalBoard,
     cell1: &(usize, u16),
     cell2: &(usize, u16),
+    accepts: &dyn Fn(&SolvingStep) -> bool,
 ) -> Option<SolvingStep> {
     let (idx1, mask1) = *cell1;
     let (idx2, mask2) = *cell2;
@@ -307,10 +498,10 @@ fn check_w_wing_pair(
     let a = cands[0];
     let b = cands[1];
 
-    if let Some(step) = check_w_wing_link(board, idx1, idx2, a, b) {
+    if let Some(step) = check_w_wing_link(board, idx1, idx2, a, b, accepts) {
         return Some(step);
     }
-    check_w_wing_link(board, idx1, idx2, b, a)
+    check_w_wing_link(board, idx1, idx2, b, a, accepts)
 }
 
 // Checks for a strong link on `link_val` connecting `idx1` and `idx2`.
@@ -321,6 +512,7 @@ fn check_w_wing_link(
     idx2: usize,
     link_val: u8,
     elim_val: u8,
+    accepts: &dyn Fn(&SolvingStep) -> bool,
 ) -> Option<SolvingStep> {
     // A strong link is a unit where `link_val` appears exactly twice.
     let link_mask = 1 << (link_val - 1);
@@ -344,31 +536,39 @@ fn check_w_wing_link(
                 // Valid W-Wing.
                 // Eliminate `elim_val` from cells seeing BOTH `idx1` and `idx2`.
                 let elims = get_common_peer_eliminations(board, idx1, idx2, elim_val);
-                if !elims.is_empty() {
-                    return Some(SolvingStep {
-                        technique: "W-Wing".to_string(),
-                        placements: vec![],
-                        eliminations: elims,
-                        cause: vec![
-                            CauseCell {
-                                index: idx1,
-                                candidates: vec![link_val, elim_val],
-                            },
-                            CauseCell {
-                                index: idx2,
-                                candidates: vec![link_val, elim_val],
-                            },
-                            // Optionally include the strong link cells in cause for highlighting
-                            CauseCell {
-                                index: p1,
-                                candidates: vec![link_val],
-                            },
-                            CauseCell {
-                                index: p2,
-                                candidates: vec![link_val],
-                            },
-                        ],
-                    });
+                if elims.is_empty() {
+                    continue;
+                }
+                let step = SolvingStep {
+                    technique: Technique::WWing,
+                    placements: vec![],
+                    eliminations: elims,
+                    cause: vec![
+                        CauseCell {
+                            index: idx1,
+                            candidates: vec![link_val, elim_val],
+                            role: None,
+                        },
+                        CauseCell {
+                            index: idx2,
+                            candidates: vec![link_val, elim_val],
+                            role: None,
+                        },
+                        // Optionally include the strong link cells in cause for highlighting
+                        CauseCell {
+                            index: p1,
+                            candidates: vec![link_val],
+                            role: None,
+                        },
+                        CauseCell {
+                            index: p2,
+                            candidates: vec![link_val],
+                            role: None,
+                        },
+                    ],
+                };
+                if accepts(&step) {
+                    return Some(step);
                 }
             }
         }
@@ -377,7 +577,7 @@ fn check_w_wing_link(
 }
 
 #[inline]
-fn are_peers(i1: usize, i2: usize) -> bool {
+pub(super) fn are_peers(i1: usize, i2: usize) -> bool {
     // Fast check using PEER_MAP is too heavy if we iterate full map.
     // Just check row/col/box.
     if i1 == i2 {
@@ -396,7 +596,7 @@ fn are_peers(i1: usize, i2: usize) -> bool {
 }
 
 #[inline]
-fn get_common_peer_eliminations(
+pub(super) fn get_common_peer_eliminations(
     board: &LogicalBoard,
     idx1: usize,
     idx2: usize,
@@ -418,3 +618,26 @@ fn get_common_peer_eliminations(
     }
     elims
 }
+
+/// Like `get_common_peer_eliminations`, for cells seeing every cell in `cells`.
+#[inline]
+pub(super) fn get_common_peer_eliminations_of(
+    board: &LogicalBoard,
+    cells: &[usize],
+    val: u8,
+) -> Vec<Elimination> {
+    let mask = 1 << (val - 1);
+    let mut elims = Vec::new();
+    for &peer in &PEER_MAP[cells[0]] {
+        if cells[1..].iter().all(|&idx| PEER_MAP[idx].contains(&peer))
+            && board.cells[peer] == 0
+            && (board.candidates[peer] & mask) != 0
+        {
+            elims.push(Elimination {
+                index: peer,
+                value: val,
+            });
+        }
+    }
+    elims
+}
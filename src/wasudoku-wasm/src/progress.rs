@@ -0,0 +1,116 @@
+/*
+* Copyright (C) 2025-2026  Henrique Almeida
+* This file is part of WASudoku.
+*
+* WASudoku is free software: you can redistribute it and/or modify
+* it under the terms of the GNU Affero General Public License as published
+* by the Free Software Foundation, either version 3 of the License, or
+* (at your option) any later version.
+*
+* WASudoku is distributed in the hope that it will be useful,
+* but WITHOUT ANY WARRANTY; without even the implied warranty of
+* MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+* GNU Affero General Public License for more details.
+*
+* You should have received a copy of the GNU Affero General Public License
+* along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::board::Board;
+use crate::logical_solver::{self, ALL_CANDIDATES, PEER_MAP, SolverConfig, TechniqueLevel};
+use crate::solver;
+use crate::types::{Elimination, Placement, ProgressReport, SolvingStep};
+
+/// Compare the player's entries and pencil marks against the unique solution, and
+/// replay the logical solve of the puzzle to find the first step they contradict.
+///
+/// ### Arguments
+///
+/// * `puzzle` - The puzzle's givens.
+/// * `entries` - The digits on the player's board, `0` for empty cells.
+/// * `masks` - The player's candidate masks, bit `d - 1` standing for digit `d`.
+///   A cell without marks is not checked.
+///
+/// ### Errors
+///
+/// Returns an `Err` if the puzzle does not have a unique solution, or if the
+/// entries hold an invalid digit or change a given.
+pub fn check_progress(
+    puzzle: &Board,
+    entries: &[u8; 81],
+    masks: &[u16; 81],
+) -> Result<ProgressReport, String> {
+    if let Some(i) = (0..81).find(|&i| entries[i] > 9) {
+        return Err(format!("Invalid digit {} at index {}", entries[i], i));
+    }
+    if let Some(i) = (0..81).find(|&i| puzzle.cells[i] != 0 && entries[i] != puzzle.cells[i]) {
+        return Err(format!("The given at index {} was changed", i));
+    }
+    if solver::count_solutions(puzzle) != 1 {
+        return Err(String::from(
+            "Invalid puzzle: it does not have a unique solution.",
+        ));
+    }
+    let mut solution = *puzzle;
+    solver::solve(&mut solution);
+
+    let wrong_placements: Vec<Placement> = (0..81)
+        .filter(|&i| entries[i] != 0 && entries[i] != solution.cells[i])
+        .map(|i| Placement {
+            index: i,
+            value: entries[i],
+        })
+        .collect();
+    let wrong_eliminations: Vec<Elimination> = (0..81)
+        .filter(|&i| {
+            let mask = masks[i] & ALL_CANDIDATES;
+            entries[i] == 0 && mask != 0 && mask & (1 << (solution.cells[i] - 1)) == 0
+        })
+        .map(|i| Elimination {
+            index: i,
+            value: solution.cells[i],
+        })
+        .collect();
+
+    // Without a mistake, no step of the solve can contradict the player. The Extreme
+    // techniques only run once the others stall, so leaving them out keeps the replay
+    // fast and its steps the same as the start of the full solve.
+    let first_divergent_step = if wrong_placements.is_empty() && wrong_eliminations.is_empty() {
+        None
+    } else {
+        let config = SolverConfig::up_to(TechniqueLevel::Master);
+        let (steps, _) = logical_solver::solve_with_config(puzzle, &config);
+        steps
+            .iter()
+            .position(|step| contradicts(step, entries, masks))
+    };
+
+    Ok(ProgressReport {
+        wrong_placements,
+        wrong_eliminations,
+        first_divergent_step,
+    })
+}
+
+/// Whether the player's board rules out the result of the step: a digit it places is
+/// missing from the cell's marks or entered in a peer, the cell holds another digit,
+/// or a digit it eliminates is entered in that cell.
+fn contradicts(step: &SolvingStep, entries: &[u8; 81], masks: &[u16; 81]) -> bool {
+    let placement_ruled_out = |p: &Placement| {
+        let mask = masks[p.index] & ALL_CANDIDATES;
+        let in_cell = if entries[p.index] != 0 {
+            entries[p.index] != p.value
+        } else {
+            mask != 0 && mask & (1 << (p.value - 1)) == 0
+        };
+        in_cell
+            || PEER_MAP[p.index]
+                .iter()
+                .any(|&peer| entries[peer] == p.value)
+    };
+    step.placements.iter().any(placement_ruled_out)
+        || step
+            .eliminations
+            .iter()
+            .any(|e| entries[e.index] == e.value)
+}
@@ -16,7 +16,9 @@
 * along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use serde::Serialize;
+use crate::logical_solver::TechniqueLevel;
+use serde::{Serialize, Serializer};
+use std::fmt;
 
 /// The final result of the solver, sent to the UI.
 #[derive(Serialize, Clone)]
@@ -27,11 +29,34 @@ pub struct SolveResult {
     pub solution: Option<String>,
 }
 
+/// The mistakes found in the player's progress, sent to the UI.
+#[derive(Serialize, Clone)]
+pub struct ProgressReport {
+    /// Cells holding a digit that differs from the solution, with that digit.
+    pub wrong_placements: Vec<Placement>,
+    /// Empty cells whose pencil marks lost the solution digit, with that digit.
+    pub wrong_eliminations: Vec<Elimination>,
+    /// The position, in the logical steps of the puzzle, of the first step whose
+    /// result the player's entries or pencil marks contradict.
+    pub first_divergent_step: Option<usize>,
+}
+
+/// The answer to "why can't this cell hold this digit?", sent to the UI.
+#[derive(Serialize, Clone)]
+pub enum CandidateExplanation {
+    /// The digit is the cell's solution, so nothing eliminates it.
+    Correct,
+    /// A step that removes the candidate.
+    Eliminated(SolvingStep),
+    /// No technique or chain of singles removes the candidate.
+    Unexplained,
+}
+
 /// A single logical step in solving the puzzle.
 #[derive(Serialize, Clone)]
 pub struct SolvingStep {
-    /// The name of the technique used (e.g., "NakedSingle").
-    pub technique: String,
+    /// The technique used, serialized as its id (e.g., "NakedSingle").
+    pub technique: Technique,
     /// A list of numbers placed on the board in this step.
     pub placements: Vec<Placement>,
     /// A list of candidates eliminated from cells in this step.
@@ -61,4 +86,308 @@ pub struct CauseCell {
     /// The candidates in this cell that are relevant to the deduction.
     /// For a Naked Pair of {1, 8}, this would be `vec![1, 8]`.
     pub candidates: Vec<u8>,
+    /// The role of this cell when a technique distinguishes between kinds of cause cells.
+    /// Omitted from the serialized output when `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<CauseRole>,
+}
+
+/// Distinguishes cause cells that play a special part in a pattern.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum CauseRole {
+    /// A fin of a Finned or Sashimi fish.
+    Fin,
+    /// A coloring group. Colors `2k` and `2k + 1` are the two opposite colors of the
+    /// `k`-th colored component.
+    Color(u8),
+    /// A cell of the numbered Almost Locked Set of an ALS technique.
+    Als(u8),
+    /// The assumption that starts a branch of a forcing chain.
+    Assumption,
+    /// A placement implied by the cause cells at the given positions in `cause`, whose
+    /// placements together removed every alternative. Together with `Assumption`, these
+    /// form the implication tree of a forcing chain.
+    Implied(Vec<u16>),
+    /// The contradiction a forcing chain branch runs into, implied by the cause cells at
+    /// the given positions. A cell without candidates lists none.
+    Contradiction(Vec<u16>),
+}
+
+/// A solving technique. Serialized as its stable id (e.g. `"X-Wing"`), which the UI
+/// relies on, so ids must never change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Technique {
+    // Basic
+    NakedSingle,
+    HiddenSingle,
+    // Intermediate
+    NakedPair,
+    NakedTriple,
+    HiddenPair,
+    HiddenTriple,
+    PointingPair,
+    PointingTriple,
+    ClaimingCandidate,
+    // Advanced
+    NakedQuad,
+    HiddenQuad,
+    XWing,
+    Swordfish,
+    FinnedXWing,
+    SashimiXWing,
+    FinnedSwordfish,
+    SashimiSwordfish,
+    XYWing,
+    XYZWing,
+    Skyscraper,
+    TwoStringKite,
+    EmptyRectangle,
+    SimpleColoringTrap,
+    SimpleColoringWrap,
+    RemotePair,
+    AlignedPairExclusion,
+    // Master
+    Jellyfish,
+    FinnedJellyfish,
+    SashimiJellyfish,
+    UniqueRectangleType1,
+    UniqueRectangleType2,
+    UniqueRectangleType3,
+    UniqueRectangleType4,
+    UniqueRectangleType5,
+    UniqueRectangleType6,
+    HiddenUniqueRectangle,
+    BugPlusOne,
+    WWing,
+    XChain,
+    XYChain,
+    AicType1,
+    AicType2,
+    DiscontinuousNiceLoop,
+    MultiColoring,
+    Medusa3D,
+    AlsXz,
+    DoublyLinkedAlsXz,
+    AlsXyWing,
+    DeathBlossom,
+    SueDeCoq,
+    WXYZWing,
+    VWXYZWing,
+    AlignedTripleExclusion,
+    FrankenXWing,
+    FrankenSwordfish,
+    FrankenJellyfish,
+    MutantXWing,
+    MutantSwordfish,
+    MutantJellyfish,
+    KrakenXWing,
+    KrakenSwordfish,
+    KrakenJellyfish,
+    // Extreme
+    JuniorExocet,
+    TemplatePlacement,
+    TemplateElimination,
+    NishioForcingChain,
+    CellForcingChain,
+    UnitForcingChain,
+}
+
+impl Technique {
+    /// Every technique, grouped by level from the simplest to the hardest.
+    pub const ALL: [Technique; 68] = [
+        Technique::NakedSingle,
+        Technique::HiddenSingle,
+        Technique::NakedPair,
+        Technique::NakedTriple,
+        Technique::HiddenPair,
+        Technique::HiddenTriple,
+        Technique::PointingPair,
+        Technique::PointingTriple,
+        Technique::ClaimingCandidate,
+        Technique::NakedQuad,
+        Technique::HiddenQuad,
+        Technique::XWing,
+        Technique::Swordfish,
+        Technique::FinnedXWing,
+        Technique::SashimiXWing,
+        Technique::FinnedSwordfish,
+        Technique::SashimiSwordfish,
+        Technique::XYWing,
+        Technique::XYZWing,
+        Technique::Skyscraper,
+        Technique::TwoStringKite,
+        Technique::EmptyRectangle,
+        Technique::SimpleColoringTrap,
+        Technique::SimpleColoringWrap,
+        Technique::RemotePair,
+        Technique::AlignedPairExclusion,
+        Technique::Jellyfish,
+        Technique::FinnedJellyfish,
+        Technique::SashimiJellyfish,
+        Technique::UniqueRectangleType1,
+        Technique::UniqueRectangleType2,
+        Technique::UniqueRectangleType3,
+        Technique::UniqueRectangleType4,
+        Technique::UniqueRectangleType5,
+        Technique::UniqueRectangleType6,
+        Technique::HiddenUniqueRectangle,
+        Technique::BugPlusOne,
+        Technique::WWing,
+        Technique::XChain,
+        Technique::XYChain,
+        Technique::AicType1,
+        Technique::AicType2,
+        Technique::DiscontinuousNiceLoop,
+        Technique::MultiColoring,
+        Technique::Medusa3D,
+        Technique::AlsXz,
+        Technique::DoublyLinkedAlsXz,
+        Technique::AlsXyWing,
+        Technique::DeathBlossom,
+        Technique::SueDeCoq,
+        Technique::WXYZWing,
+        Technique::VWXYZWing,
+        Technique::AlignedTripleExclusion,
+        Technique::FrankenXWing,
+        Technique::FrankenSwordfish,
+        Technique::FrankenJellyfish,
+        Technique::MutantXWing,
+        Technique::MutantSwordfish,
+        Technique::MutantJellyfish,
+        Technique::KrakenXWing,
+        Technique::KrakenSwordfish,
+        Technique::KrakenJellyfish,
+        Technique::JuniorExocet,
+        Technique::TemplatePlacement,
+        Technique::TemplateElimination,
+        Technique::NishioForcingChain,
+        Technique::CellForcingChain,
+        Technique::UnitForcingChain,
+    ];
+
+    /// The stable id used in the serialized output.
+    pub fn id(self) -> &'static str {
+        self.info().0
+    }
+
+    /// The human-readable name of the technique.
+    pub fn name(self) -> &'static str {
+        self.info().1
+    }
+
+    /// The difficulty level of the technique.
+    pub fn level(self) -> TechniqueLevel {
+        self.info().2
+    }
+
+    /// Looks a technique up by its serialized id.
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.id() == id)
+    }
+
+    fn info(self) -> (&'static str, &'static str, TechniqueLevel) {
+        use TechniqueLevel::{Advanced, Basic, Extreme, Intermediate, Master};
+        match self {
+            Self::NakedSingle => ("NakedSingle", "Naked Single", Basic),
+            Self::HiddenSingle => ("HiddenSingle", "Hidden Single", Basic),
+            Self::NakedPair => ("NakedPair", "Naked Pair", Intermediate),
+            Self::NakedTriple => ("NakedTriple", "Naked Triple", Intermediate),
+            Self::HiddenPair => ("HiddenPair", "Hidden Pair", Intermediate),
+            Self::HiddenTriple => ("HiddenTriple", "Hidden Triple", Intermediate),
+            Self::PointingPair => ("PointingPair", "Pointing Pair", Intermediate),
+            Self::PointingTriple => ("PointingTriple", "Pointing Triple", Intermediate),
+            Self::ClaimingCandidate => ("ClaimingCandidate", "Claiming Candidate", Intermediate),
+            Self::NakedQuad => ("NakedQuad", "Naked Quad", Advanced),
+            Self::HiddenQuad => ("HiddenQuad", "Hidden Quad", Advanced),
+            Self::XWing => ("X-Wing", "X-Wing", Advanced),
+            Self::Swordfish => ("Swordfish", "Swordfish", Advanced),
+            Self::FinnedXWing => ("FinnedX-Wing", "Finned X-Wing", Advanced),
+            Self::SashimiXWing => ("SashimiX-Wing", "Sashimi X-Wing", Advanced),
+            Self::FinnedSwordfish => ("FinnedSwordfish", "Finned Swordfish", Advanced),
+            Self::SashimiSwordfish => ("SashimiSwordfish", "Sashimi Swordfish", Advanced),
+            Self::XYWing => ("XY-Wing", "XY-Wing", Advanced),
+            Self::XYZWing => ("XYZ-Wing", "XYZ-Wing", Advanced),
+            Self::Skyscraper => ("Skyscraper", "Skyscraper", Advanced),
+            Self::TwoStringKite => ("TwoStringKite", "2-String Kite", Advanced),
+            Self::EmptyRectangle => ("EmptyRectangle", "Empty Rectangle", Advanced),
+            Self::SimpleColoringTrap => ("SimpleColoringTrap", "Simple Coloring (Trap)", Advanced),
+            Self::SimpleColoringWrap => ("SimpleColoringWrap", "Simple Coloring (Wrap)", Advanced),
+            Self::RemotePair => ("RemotePair", "Remote Pair", Advanced),
+            Self::AlignedPairExclusion => {
+                ("AlignedPairExclusion", "Aligned Pair Exclusion", Advanced)
+            }
+            Self::Jellyfish => ("Jellyfish", "Jellyfish", Master),
+            Self::FinnedJellyfish => ("FinnedJellyfish", "Finned Jellyfish", Master),
+            Self::SashimiJellyfish => ("SashimiJellyfish", "Sashimi Jellyfish", Master),
+            Self::UniqueRectangleType1 => {
+                ("UniqueRectangleType1", "Unique Rectangle Type 1", Master)
+            }
+            Self::UniqueRectangleType2 => {
+                ("UniqueRectangleType2", "Unique Rectangle Type 2", Master)
+            }
+            Self::UniqueRectangleType3 => {
+                ("UniqueRectangleType3", "Unique Rectangle Type 3", Master)
+            }
+            Self::UniqueRectangleType4 => {
+                ("UniqueRectangleType4", "Unique Rectangle Type 4", Master)
+            }
+            Self::UniqueRectangleType5 => {
+                ("UniqueRectangleType5", "Unique Rectangle Type 5", Master)
+            }
+            Self::UniqueRectangleType6 => {
+                ("UniqueRectangleType6", "Unique Rectangle Type 6", Master)
+            }
+            Self::HiddenUniqueRectangle => {
+                ("HiddenUniqueRectangle", "Hidden Unique Rectangle", Master)
+            }
+            Self::BugPlusOne => ("BUG+1", "BUG+1", Master),
+            Self::WWing => ("W-Wing", "W-Wing", Master),
+            Self::XChain => ("X-Chain", "X-Chain", Master),
+            Self::XYChain => ("XY-Chain", "XY-Chain", Master),
+            Self::AicType1 => ("AICType1", "AIC Type 1", Master),
+            Self::AicType2 => ("AICType2", "AIC Type 2", Master),
+            Self::DiscontinuousNiceLoop => {
+                ("DiscontinuousNiceLoop", "Discontinuous Nice Loop", Master)
+            }
+            Self::MultiColoring => ("MultiColoring", "Multi-Coloring", Master),
+            Self::Medusa3D => ("3DMedusa", "3D Medusa", Master),
+            Self::AlsXz => ("ALS-XZ", "ALS-XZ", Master),
+            Self::DoublyLinkedAlsXz => ("DoublyLinkedALS-XZ", "Doubly Linked ALS-XZ", Master),
+            Self::AlsXyWing => ("ALS-XY-Wing", "ALS-XY-Wing", Master),
+            Self::DeathBlossom => ("DeathBlossom", "Death Blossom", Master),
+            Self::SueDeCoq => ("SueDeCoq", "Sue de Coq", Master),
+            Self::WXYZWing => ("WXYZ-Wing", "WXYZ-Wing", Master),
+            Self::VWXYZWing => ("VWXYZ-Wing", "VWXYZ-Wing", Master),
+            Self::AlignedTripleExclusion => {
+                ("AlignedTripleExclusion", "Aligned Triple Exclusion", Master)
+            }
+            Self::FrankenXWing => ("FrankenX-Wing", "Franken X-Wing", Master),
+            Self::FrankenSwordfish => ("FrankenSwordfish", "Franken Swordfish", Master),
+            Self::FrankenJellyfish => ("FrankenJellyfish", "Franken Jellyfish", Master),
+            Self::MutantXWing => ("MutantX-Wing", "Mutant X-Wing", Master),
+            Self::MutantSwordfish => ("MutantSwordfish", "Mutant Swordfish", Master),
+            Self::MutantJellyfish => ("MutantJellyfish", "Mutant Jellyfish", Master),
+            Self::KrakenXWing => ("KrakenX-Wing", "Kraken X-Wing", Master),
+            Self::KrakenSwordfish => ("KrakenSwordfish", "Kraken Swordfish", Master),
+            Self::KrakenJellyfish => ("KrakenJellyfish", "Kraken Jellyfish", Master),
+            Self::JuniorExocet => ("JuniorExocet", "Junior Exocet", Extreme),
+            Self::TemplatePlacement => ("TemplatePlacement", "Template Placement", Extreme),
+            Self::TemplateElimination => ("TemplateElimination", "Template Elimination", Extreme),
+            Self::NishioForcingChain => ("NishioForcingChain", "Nishio Forcing Chain", Extreme),
+            Self::CellForcingChain => ("CellForcingChain", "Cell Forcing Chain", Extreme),
+            Self::UnitForcingChain => ("UnitForcingChain", "Unit Forcing Chain", Extreme),
+        }
+    }
+}
+
+impl Serialize for Technique {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.id())
+    }
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
 }
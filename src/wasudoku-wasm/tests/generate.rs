@@ -33,7 +33,7 @@ fn test_generate_creates_valid_puzzle() {
         "Generated puzzle should not be empty."
     );
     assert!(
-        puzzle.cells.iter().any(|&c| c == 0),
+        puzzle.cells.contains(&0),
         "Generated puzzle should not be full."
     );
 }
@@ -113,11 +113,16 @@ fn test_generate_extreme_puzzle_difficulty() {
         "Extreme puzzle must still have a unique solution."
     );
 
-    let (_, solved_board) = logical_solver::solve_with_steps(&puzzle);
+    let (steps, solved_board) = logical_solver::solve_with_steps(&puzzle);
+    let stats = logical_solver::analyze_difficulty(&steps);
 
-    let is_completely_solved = solved_board.cells.iter().all(|&c| c != 0);
     assert!(
-        !is_completely_solved,
-        "Extreme puzzle must NOT be completely solvable with only logic techniques (requires backtracking)."
+        solved_board.cells.iter().all(|&c| c != 0),
+        "Extreme puzzle must be fully solvable with Extreme techniques."
+    );
+    assert_eq!(
+        stats.max_level,
+        TechniqueLevel::Extreme,
+        "Extreme puzzle must require Extreme techniques."
     );
 }
@@ -17,8 +17,15 @@
 */
 
 use wasudoku_wasm::board::Board;
-use wasudoku_wasm::logical_solver::{self, LogicalBoard, TechniqueLevel, analyze_difficulty};
-use wasudoku_wasm::types::{Elimination, SolvingStep};
+use wasudoku_wasm::logical_solver::explain::explain_candidate;
+use wasudoku_wasm::logical_solver::uniqueness;
+use wasudoku_wasm::logical_solver::{
+    self, LogicalBoard, SolverConfig, TechniqueLevel, analyze_difficulty,
+};
+use wasudoku_wasm::solver;
+use wasudoku_wasm::types::{
+    CandidateExplanation, CauseCell, CauseRole, Elimination, SolvingStep, Technique,
+};
 
 fn board_from_str(s: &str) -> LogicalBoard {
     let simple_board: Board = s.parse().unwrap();
@@ -28,7 +35,7 @@ fn board_from_str(s: &str) -> LogicalBoard {
 fn assert_nth_logical_step(
     puzzle_str: &str,
     step_index: usize,
-    expected_technique: &str,
+    expected_technique: Technique,
 ) -> SolvingStep {
     let initial_board: Board = puzzle_str.parse().unwrap();
     let (steps, _) = logical_solver::solve_with_steps(&initial_board);
@@ -46,6 +53,24 @@ fn assert_nth_logical_step(
     step.clone()
 }
 
+/// Solves with the default techniques, but tries `technique` before all others, for
+/// puzzles where simpler techniques would otherwise make it unnecessary.
+fn solve_preferring(puzzle_str: &str, technique: Technique) -> Vec<SolvingStep> {
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let mut config = SolverConfig::default();
+    config.techniques.retain(|&t| t != technique);
+    config.techniques.insert(0, technique);
+    logical_solver::solve_with_config(&initial_board, &config).0
+}
+
+fn assert_technique_used(puzzle_str: &str, technique: Technique) {
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
+
+    let used = steps.iter().any(|s| s.technique == technique);
+    assert!(used, "Expected {} technique usage", technique);
+}
+
 #[test]
 fn test_candidate_initialization() {
     let puzzle_str =
@@ -65,7 +90,7 @@ fn test_candidate_initialization() {
 fn test_naked_single_step_generation() {
     let puzzle_str =
         "...2..7...5..96832.8.7....641.....78.2..745..7.31854....2531..4.3164..5...9...61.";
-    let first_step = assert_nth_logical_step(puzzle_str, 0, "NakedSingle");
+    let first_step = assert_nth_logical_step(puzzle_str, 0, Technique::NakedSingle);
 
     assert_eq!(first_step.placements[0].index, 9);
     assert_eq!(first_step.placements[0].value, 1);
@@ -84,7 +109,7 @@ fn test_naked_single_step_generation() {
 fn test_hidden_single_detection_in_box() {
     let puzzle_str =
         ".38.917.571...38.9...78.3419738526148649175325213..9781..67..83386.29.57..7.38.96";
-    let first_step = assert_nth_logical_step(puzzle_str, 0, "HiddenSingle");
+    let first_step = assert_nth_logical_step(puzzle_str, 0, Technique::HiddenSingle);
 
     assert_eq!(first_step.placements[0].index, 0);
     assert_eq!(first_step.placements[0].value, 4);
@@ -107,11 +132,11 @@ fn test_hidden_single_with_naked_single_scenario() {
     let board = board_from_str(puzzle_str);
 
     // Call find_hidden_single directly
-    let step = logical_solver::basic::find_hidden_single(&board);
+    let step = logical_solver::basic::find_hidden_single(&board, &|_| true);
 
     assert!(step.is_some());
     let s = step.unwrap();
-    assert_eq!(s.technique, "HiddenSingle");
+    assert_eq!(s.technique, Technique::HiddenSingle);
     assert_eq!(s.placements[0].index, 0);
     assert_eq!(s.placements[0].value, 1);
 
@@ -126,7 +151,7 @@ fn test_hidden_single_with_naked_single_scenario() {
 fn test_naked_pair_detection() {
     let puzzle_str =
         ".....8..5..97...1..1.....687.51..........3..46......57.6...5.9..8........4.9.....";
-    let step = assert_nth_logical_step(puzzle_str, 31, "NakedPair");
+    let step = assert_nth_logical_step(puzzle_str, 31, Technique::NakedPair);
 
     assert_eq!(step.cause.len(), 2);
     assert!(step.cause.iter().any(|c| c.index == 14));
@@ -147,7 +172,7 @@ fn test_naked_pair_detection() {
 fn test_pointing_pair_detection() {
     let puzzle_str =
         ".....8..5..97...1..1.....687.51..........3..46......57.6...5.9..8........4.9.....";
-    let step = assert_nth_logical_step(puzzle_str, 32, "PointingPair");
+    let step = assert_nth_logical_step(puzzle_str, 32, Technique::PointingPair);
 
     assert_eq!(step.cause.len(), 2);
     assert!(step.cause.iter().any(|c| c.index == 15));
@@ -170,7 +195,7 @@ fn test_claiming_candidate_detection() {
 
     let step = steps
         .iter()
-        .find(|s| s.technique == "ClaimingCandidate")
+        .find(|s| s.technique == Technique::ClaimingCandidate)
         .expect("Expected a ClaimingCandidate step");
 
     assert!(!step.eliminations.is_empty());
@@ -184,7 +209,7 @@ fn test_hidden_pair_detection() {
     let initial_board: Board = puzzle_str.parse().unwrap();
     let (steps, _) = logical_solver::solve_with_steps(&initial_board);
 
-    let has_hidden_pair = steps.iter().any(|s| s.technique == "HiddenPair");
+    let has_hidden_pair = steps.iter().any(|s| s.technique == Technique::HiddenPair);
     assert!(has_hidden_pair, "Expected HiddenPair technique usage");
 }
 
@@ -195,7 +220,7 @@ fn test_naked_triple_detection() {
     let initial_board: Board = puzzle_str.parse().unwrap();
     let (steps, _) = logical_solver::solve_with_steps(&initial_board);
 
-    let has_naked_triple = steps.iter().any(|s| s.technique == "NakedTriple");
+    let has_naked_triple = steps.iter().any(|s| s.technique == Technique::NakedTriple);
     assert!(has_naked_triple, "Expected NakedTriple technique usage");
 }
 
@@ -206,7 +231,9 @@ fn test_pointing_triple_detection() {
     let initial_board: Board = puzzle_str.parse().unwrap();
     let (steps, _) = logical_solver::solve_with_steps(&initial_board);
 
-    let has_pointing_triple = steps.iter().any(|s| s.technique == "PointingTriple");
+    let has_pointing_triple = steps
+        .iter()
+        .any(|s| s.technique == Technique::PointingTriple);
     assert!(
         has_pointing_triple,
         "Expected PointingTriple technique usage"
@@ -222,7 +249,7 @@ fn test_x_wing_detection() {
 
     let step = steps
         .iter()
-        .find(|s| s.technique == "X-Wing")
+        .find(|s| s.technique == Technique::XWing)
         .expect("Expected an X-Wing step");
 
     let x_wing_val = step.cause[0].candidates[0];
@@ -240,7 +267,7 @@ fn test_swordfish_detection() {
     let initial_board: Board = puzzle_str.parse().unwrap();
     let (steps, _) = logical_solver::solve_with_steps(&initial_board);
 
-    let has_swordfish = steps.iter().any(|s| s.technique == "Swordfish");
+    let has_swordfish = steps.iter().any(|s| s.technique == Technique::Swordfish);
     assert!(has_swordfish, "Expected Swordfish technique usage");
 }
 
@@ -251,7 +278,7 @@ fn test_xy_wing_detection() {
     let initial_board: Board = puzzle_str.parse().unwrap();
     let (steps, _) = logical_solver::solve_with_steps(&initial_board);
 
-    let has_xy_wing = steps.iter().any(|s| s.technique == "XY-Wing");
+    let has_xy_wing = steps.iter().any(|s| s.technique == Technique::XYWing);
     assert!(has_xy_wing, "Expected XY-Wing technique usage");
 }
 
@@ -262,7 +289,7 @@ fn test_xyz_wing_detection() {
     let initial_board: Board = puzzle_str.parse().unwrap();
     let (steps, _) = logical_solver::solve_with_steps(&initial_board);
 
-    let has_xyz_wing = steps.iter().any(|s| s.technique == "XYZ-Wing");
+    let has_xyz_wing = steps.iter().any(|s| s.technique == Technique::XYZWing);
     assert!(has_xyz_wing, "Expected XYZ-Wing technique usage");
 }
 
@@ -273,7 +300,7 @@ fn test_skyscraper_detection() {
     let initial_board: Board = puzzle_str.parse().unwrap();
     let (steps, _) = logical_solver::solve_with_steps(&initial_board);
 
-    let has_skyscraper = steps.iter().any(|s| s.technique == "Skyscraper");
+    let has_skyscraper = steps.iter().any(|s| s.technique == Technique::Skyscraper);
     assert!(has_skyscraper, "Expected Skyscraper technique usage");
 }
 
@@ -284,10 +311,35 @@ fn test_two_string_kite_detection() {
     let initial_board: Board = puzzle_str.parse().unwrap();
     let (steps, _) = logical_solver::solve_with_steps(&initial_board);
 
-    let has_kite = steps.iter().any(|s| s.technique == "TwoStringKite");
+    let has_kite = steps
+        .iter()
+        .any(|s| s.technique == Technique::TwoStringKite);
     assert!(has_kite, "Expected Two-String Kite technique usage");
 }
 
+#[test]
+fn test_empty_rectangle_detection() {
+    let puzzle_str =
+        "..7.38....3.216...2..9...3..7......31....4.8.8.....49279....6.1....62.79.........";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
+
+    let step = steps
+        .iter()
+        .find(|s| s.technique == Technique::EmptyRectangle)
+        .expect("Expected an Empty Rectangle step");
+
+    // Box cells followed by the two ends of the strong link.
+    assert!(step.cause.len() >= 4);
+    assert_eq!(step.eliminations.len(), 1);
+    let num = step.eliminations[0].value;
+    assert!(step.cause.iter().all(|c| c.candidates == vec![num]));
+
+    let box_of = |idx: usize| (idx / 27) * 3 + (idx % 9) / 3;
+    let target = step.eliminations[0].index;
+    assert_ne!(box_of(target), box_of(step.cause[0].index));
+}
+
 #[test]
 fn test_jellyfish_detection() {
     // A known pattern that requires a Jellyfish.
@@ -296,20 +348,362 @@ fn test_jellyfish_detection() {
     let initial_board: Board = puzzle_str.parse().unwrap();
     let (steps, _) = logical_solver::solve_with_steps(&initial_board);
 
-    let has_jellyfish = steps.iter().any(|s| s.technique == "Jellyfish");
+    let has_jellyfish = steps.iter().any(|s| s.technique == Technique::Jellyfish);
     assert!(has_jellyfish, "Expected Jellyfish technique usage");
 }
 
+#[test]
+fn test_finned_x_wing_detection() {
+    let puzzle_str =
+        "9.175.....5..2.....2.9..7.84.9....65.........86....4.31.3..5.7.....6..1.....795.4";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
+
+    let step = steps
+        .iter()
+        .find(|s| s.technique == Technique::FinnedXWing)
+        .expect("Expected a Finned X-Wing step");
+
+    let fins: Vec<&CauseCell> = step
+        .cause
+        .iter()
+        .filter(|c| c.role == Some(CauseRole::Fin))
+        .collect();
+    assert!(!fins.is_empty(), "Fin cells should be marked in the cause");
+
+    // Every elimination must share the box of the fins.
+    let box_of = |idx: usize| (idx / 27) * 3 + (idx % 9) / 3;
+    let fin_box = box_of(fins[0].index);
+    assert!(fins.iter().all(|f| box_of(f.index) == fin_box));
+    assert!(step.eliminations.iter().all(|e| box_of(e.index) == fin_box));
+}
+
+#[test]
+fn test_sashimi_x_wing_detection() {
+    assert_technique_used(
+        ".683....1..4.5..6.7..9..3.....4...12..2...8..41...5.....7..2..5.3..1.4..1....927.",
+        Technique::SashimiXWing,
+    );
+}
+
+#[test]
+fn test_finned_swordfish_detection() {
+    assert_technique_used(
+        "..1.4.....42.8.6..7..6.1...5....3.94....6....29.4....7...5.4..8..5.9.21.....3.4..",
+        Technique::FinnedSwordfish,
+    );
+}
+
+#[test]
+fn test_sashimi_swordfish_detection() {
+    assert_technique_used(
+        ".....1..6.31..68..2...4.7..4.8.2...3...6.4...1...5.4.2..2.1...9..49..15.6..5.....",
+        Technique::SashimiSwordfish,
+    );
+}
+
+#[test]
+fn test_franken_x_wing_detection() {
+    // Digit 1 sits only in r1c1, r1c5, r2c2, r2c5, r3c3 and r6c5.
+    // Rows 1 and 2 are covered by box 1 and column 5.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [0b110; 81],
+    };
+    for idx in [0, 4, 10, 13, 20, 49] {
+        board.candidates[idx] |= 1;
+    }
+
+    // The opt-in finder is the only one that sees it, once the config enables it.
+    assert!(logical_solver::fish::find_fish_techniques(&board, &|_| true).is_none());
+    let config = SolverConfig {
+        techniques: vec![Technique::XWing, Technique::FrankenXWing],
+        max_level: TechniqueLevel::Master,
+    };
+    let step =
+        logical_solver::find_next_step(&board, &config).expect("Should find a Franken X-Wing");
+
+    assert_eq!(step.technique, Technique::FrankenXWing);
+    let cause: Vec<usize> = step.cause.iter().map(|c| c.index).collect();
+    assert_eq!(cause, vec![0, 4, 10, 13]);
+    let elims: Vec<(usize, u8)> = step
+        .eliminations
+        .iter()
+        .map(|e| (e.index, e.value))
+        .collect();
+    assert_eq!(elims, vec![(20, 1), (49, 1)]);
+}
+
+#[test]
+fn test_kraken_x_wing_detection() {
+    let puzzle_str =
+        "..9..7.28.4..2.3..6....9....9....2...648.197...5....3....1....2..1.8..9.87.9..4..";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let mut solution = initial_board;
+    assert!(solver::solve(&mut solution));
+
+    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
+    let step = steps
+        .iter()
+        .find(|s| s.technique == Technique::KrakenXWing)
+        .expect("Expected KrakenX-Wing technique usage");
+
+    // Every fin starts its own chain to the eliminated candidate.
+    let fins = step
+        .cause
+        .iter()
+        .filter(|c| c.role == Some(CauseRole::Fin))
+        .count();
+    let chains = step
+        .cause
+        .iter()
+        .filter(|c| c.role == Some(CauseRole::Assumption))
+        .count();
+    assert!(fins >= 2);
+    assert_eq!(fins, chains);
+    assert_eq!(step.technique.level(), TechniqueLevel::Master);
+
+    assert_eq!(step.eliminations.len(), 1);
+    let elim = &step.eliminations[0];
+    assert_ne!(solution.cells[elim.index], elim.value);
+}
+
+#[test]
+fn test_kraken_fish_with_fins_in_one_box() {
+    // X-Wing on 1 in rows 1 and 5, columns 1 and 5, with a fin in r1c8. No cover cell
+    // shares the fin's box, but if the fin is true, r8c8 = 2 and then r8c2 = 1, which
+    // removes 1 from column 1 in box 7 all the same.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [511; 81],
+    };
+    for idx in (0..9).chain(36..45) {
+        board.candidates[idx] &= !1;
+    }
+    for idx in [0, 4, 7, 36, 40] {
+        board.candidates[idx] |= 1;
+    }
+    board.candidates[64] = 0b11;
+    board.candidates[70] = 0b11;
+
+    assert!(logical_solver::fish::find_finned_fish(&board, &|_| true).is_none());
+    let step =
+        logical_solver::fish::find_kraken_fish(&board, &|_| true).expect("Expected a Kraken fish");
+
+    assert_eq!(step.technique, Technique::KrakenXWing);
+    let fins: Vec<usize> = step
+        .cause
+        .iter()
+        .filter(|c| c.role == Some(CauseRole::Fin))
+        .map(|c| c.index)
+        .collect();
+    assert_eq!(fins, vec![7]);
+    assert_eq!(step.eliminations.len(), 1);
+    assert_eq!(
+        (step.eliminations[0].index, step.eliminations[0].value),
+        (54, 1)
+    );
+}
+
+#[test]
+fn test_kraken_fish_keeps_digit_placed_by_fin_chain() {
+    // A fin's chain places 1 in r5c5, a cover cell of the X-Wing; placing a digit must
+    // not count as removing it, or r5c5 would wrongly lose its 1.
+    let puzzle_str =
+        "1.8..3.9..2.4.....4.......3.81.4..5......783..562..7.4.1...........5......9..12.7";
+    let board: LogicalBoard = "1 67 8 567 267 3 456 9 256 35679 2 357 4 16789 5689 156 678 1568 \
+        4 679 57 156789 126789 25689 156 2678 3 2379 8 1 369 4 69 69 5 269 \
+        29 49 24 1569 169 7 8 3 269 39 5 6 2 389 89 7 1 4 \
+        235678 1 23457 36789 236789 24689 34569 468 5689 23678 3467 2347 36789 5 24689 13469 468 1689 \
+        3568 346 9 368 368 1 2 468 7"
+        .parse()
+        .unwrap();
+    let mut solution: Board = puzzle_str.parse().unwrap();
+    assert!(solver::solve(&mut solution));
+    assert_eq!(solution.cells[40], 1);
+
+    let step =
+        logical_solver::fish::find_kraken_fish(&board, &|_| true).expect("Expected a Kraken fish");
+    for e in &step.eliminations {
+        assert_ne!(solution.cells[e.index], e.value);
+    }
+}
+
 #[test]
 fn test_unique_rectangle_type1_detection() {
     // A specific layout that has a deadly pattern UR Type 1
     let puzzle_str =
         ".....3....4.91.7..9.6....43.2......4...675...3......7.27....6.1..5.69.2....2.....";
+    let steps = solve_preferring(puzzle_str, Technique::UniqueRectangleType1);
+
+    let has_ur = steps
+        .iter()
+        .any(|s| s.technique == Technique::UniqueRectangleType1);
+    assert!(has_ur, "Expected Unique Rectangle Type 1 technique usage");
+}
+
+#[test]
+fn test_unique_rectangle_type1_cause() {
+    // r1c1, r1c4 and r2c1 hold {1,2}, the roof r2c4 holds {1,2,3}.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [0b1_1111_1111; 81],
+    };
+    for idx in [0, 3, 9] {
+        board.candidates[idx] = 0b011;
+    }
+    board.candidates[12] = 0b111;
+
+    let step = logical_solver::uniqueness::find_unique_rectangle_type_1(&board, &|_| true)
+        .expect("Expected a Unique Rectangle Type 1");
+    let cause: Vec<(usize, Vec<u8>)> = step
+        .cause
+        .iter()
+        .map(|c| (c.index, c.candidates.clone()))
+        .collect();
+    assert_eq!(
+        cause,
+        vec![(0, vec![1, 2]), (3, vec![1, 2]), (9, vec![1, 2])]
+    );
+    let elims: Vec<(usize, u8)> = step
+        .eliminations
+        .iter()
+        .map(|e| (e.index, e.value))
+        .collect();
+    assert_eq!(elims, vec![(12, 1), (12, 2)]);
+}
+
+#[test]
+fn test_unique_rectangle_type2_detection() {
+    let steps = solve_preferring(
+        "4..63...2.38.5.4.1.5..........876....7.....8....315..........6.3.9.2.81.2...64..9",
+        Technique::UniqueRectangleType2,
+    );
+
+    let used = steps
+        .iter()
+        .any(|s| s.technique == Technique::UniqueRectangleType2);
+    assert!(
+        used,
+        "Expected {} technique usage",
+        Technique::UniqueRectangleType2
+    );
+}
+
+#[test]
+fn test_unique_rectangle_type3_detection() {
+    assert_technique_used(
+        ".8.........56..78..3.7.2..5...56.3.9.........5.4.23...2..8.9.4..48..65.........6.",
+        Technique::UniqueRectangleType3,
+    );
+}
+
+#[test]
+fn test_unique_rectangle_type4_detection() {
+    assert_technique_used(
+        "..62....9..3..9.865...8.24....3.....7.4...6.2.....7....15.3...742.7..3..9....14..",
+        Technique::UniqueRectangleType4,
+    );
+}
+
+#[test]
+fn test_unique_rectangle_type5_found() {
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [511; 81],
+    };
+
+    // Rectangle r1c1, r1c4, r2c1, r2c4 on {1, 2}; the anti-diagonal also holds a 3.
+    board.candidates[0] = 0b011;
+    board.candidates[12] = 0b011;
+    board.candidates[3] = 0b111;
+    board.candidates[9] = 0b111;
+
+    let step = logical_solver::uniqueness::find_unique_rectangle_type_5(&board, &|_| true)
+        .expect("Should find UniqueRectangleType5");
+
+    assert_eq!(step.technique, Technique::UniqueRectangleType5);
+    assert!(step.eliminations.iter().all(|e| e.value == 3));
+    // r1c2 sees r1c4 (row) and r2c1 (box).
+    assert!(step.eliminations.iter().any(|e| e.index == 1));
+    assert!(
+        !step
+            .eliminations
+            .iter()
+            .any(|e| e.index == 3 || e.index == 9)
+    );
+}
+
+#[test]
+fn test_unique_rectangle_type6_detection() {
+    assert_technique_used(
+        "9.175.....5..2.....2.9..7.84.9....65.........86....4.31.3..5.7.....6..1.....795.4",
+        Technique::UniqueRectangleType6,
+    );
+}
+
+#[test]
+fn test_unique_rectangle_type6_confined_to_rows_only() {
+    // 2 is confined to the rectangle in rows 4 and 5 but not in columns 2 and 5.
+    let puzzle_str =
+        "7.........8.24...9..1..9.8....6.54..4.......56..8...7...23.8.5.....1..9..6....82.";
+    let board: LogicalBoard = "7 39 39 5 8 6 2 4 1 5 8 6 2 4 1 7 3 9 2 4 1 7 3 9 5 8 6 \
+        3 129 7 6 29 5 4 1 8 4 29 8 19 279 3 39 6 5 6 159 59 8 29 4 39 7 2 \
+        9 7 2 3 6 8 1 5 4 8 35 345 4 1 2 6 9 7 1 6 45 49 59 7 8 2 3"
+        .parse()
+        .unwrap();
+    let mut solution: Board = puzzle_str.parse().unwrap();
+    assert!(solver::solve(&mut solution));
+
+    let step = uniqueness::find_unique_rectangle_type_6(&board, &|_| true)
+        .expect("Expected a Unique Rectangle Type 6 step");
+    let mut elims: Vec<(usize, u8)> = step
+        .eliminations
+        .iter()
+        .map(|e| (e.index, e.value))
+        .collect();
+    elims.sort();
+    assert_eq!(elims, vec![(28, 2), (40, 2)]);
+    assert!(
+        elims
+            .iter()
+            .all(|&(idx, value)| solution.cells[idx] != value)
+    );
+}
+
+#[test]
+fn test_hidden_unique_rectangle_detection() {
+    assert_technique_used(
+        "..1.7.5.....5.3.8...3..19...1.2....7.5.....2.2....4.6...58..3...9.4.5.....4.9.7..",
+        Technique::HiddenUniqueRectangle,
+    );
+}
+
+#[test]
+fn test_bug_plus_one_detection() {
+    let puzzle_str =
+        ".79...5.64..58..7.....79.2......48...5.....3...42......1.73.....4..65..16.8...25.";
     let initial_board: Board = puzzle_str.parse().unwrap();
     let (steps, _) = logical_solver::solve_with_steps(&initial_board);
 
-    let has_ur = steps.iter().any(|s| s.technique == "UniqueRectangleType1");
-    assert!(has_ur, "Expected Unique Rectangle Type 1 technique usage");
+    let step = steps
+        .iter()
+        .find(|s| s.technique == Technique::BugPlusOne)
+        .expect("Expected a BUG+1 step");
+
+    assert_eq!(step.placements.len(), 1);
+    assert_eq!(step.cause.len(), 1);
+    assert_eq!(step.cause[0].index, step.placements[0].index);
+    assert_eq!(step.cause[0].candidates.len(), 3);
+    assert!(step.cause[0].candidates.contains(&step.placements[0].value));
+}
+
+#[test]
+fn test_bug_plus_one_requires_single_trivalue_cell() {
+    let board = board_from_str(
+        ".79...5.64..58..7.....79.2......48...5.....3...42......1.73.....4..65..16.8...25.",
+    );
+    assert!(logical_solver::uniqueness::find_bug_plus_one(&board, &|_| true).is_none());
 }
 
 #[test]
@@ -317,73 +711,901 @@ fn test_w_wing_detection() {
     // Puzzle known to require W-Wing
     let puzzle_str =
         "4..2....9..16...7..8.4....17.4....9.....4.....9....7.65....3.2..2...61..9....4..7";
-    let initial_board: Board = puzzle_str.parse().unwrap();
-    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
+    let steps = solve_preferring(puzzle_str, Technique::WWing);
 
-    let has_w_wing = steps.iter().any(|s| s.technique == "W-Wing");
+    let has_w_wing = steps.iter().any(|s| s.technique == Technique::WWing);
     assert!(has_w_wing, "Expected W-Wing technique usage");
 }
 
 #[test]
-fn test_logical_board_set_cell_returns_false_if_filled() {
+fn test_wxyz_wing_found() {
+    // Pivot r1c1 {1,2,3,4} with wings r2c2 {1,4}, r3c3 {2,4} and r1c7 {3,4}.
     let mut board = LogicalBoard {
         cells: [0; 81],
-        candidates: [0; 81],
+        candidates: [511; 81],
     };
-    board.cells[0] = 5;
-    let result = board.set_cell(0, 1);
+    board.candidates[0] = 0b1111;
+    board.candidates[10] = 0b1001;
+    board.candidates[20] = 0b1010;
+    board.candidates[6] = 0b1100;
+
+    let step = logical_solver::wings::find_bent_naked_subset(&board, &|_| true)
+        .expect("Should find WXYZ-Wing");
+
+    assert_eq!(step.technique, Technique::WXYZWing);
+    assert_eq!(step.cause.len(), 4);
+    // Only r1c2 and r1c3 see every cell holding 4.
+    let elims: Vec<(usize, u8)> = step
+        .eliminations
+        .iter()
+        .map(|e| (e.index, e.value))
+        .collect();
+    assert_eq!(elims.len(), 2);
+    assert!(elims.contains(&(1, 4)) && elims.contains(&(2, 4)));
+}
+
+#[test]
+fn test_bent_naked_subset_names_wings_by_size() {
+    // Pivot r1c1 with wings r2c2 {1,3} and r1c7 {2,3}: an XY-Wing with a bivalue pivot
+    // {1,2}, an XYZ-Wing with a trivalue pivot {1,2,3}.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [511; 81],
+    };
+    board.candidates[10] = 0b101;
+    board.candidates[6] = 0b110;
+
+    board.candidates[0] = 0b011;
+    let step = logical_solver::wings::find_bent_naked_subset(&board, &|_| true)
+        .expect("Should find XY-Wing");
+    assert_eq!(step.technique, Technique::XYWing);
+    let elims: Vec<(usize, u8)> = step
+        .eliminations
+        .iter()
+        .map(|e| (e.index, e.value))
+        .collect();
+    assert_eq!(elims.len(), 5);
     assert!(
-        !result,
-        "set_cell should return false if cell is already filled"
+        [1, 2, 15, 16, 17]
+            .iter()
+            .all(|&idx| elims.contains(&(idx, 3)))
+    );
+
+    board.candidates[0] = 0b111;
+    let step = logical_solver::wings::find_bent_naked_subset(&board, &|_| true)
+        .expect("Should find XYZ-Wing");
+    assert_eq!(step.technique, Technique::XYZWing);
+    let elims: Vec<(usize, u8)> = step
+        .eliminations
+        .iter()
+        .map(|e| (e.index, e.value))
+        .collect();
+    assert_eq!(elims.len(), 2);
+    assert!(elims.contains(&(1, 3)) && elims.contains(&(2, 3)));
+}
+
+#[test]
+fn test_bent_naked_subset_of_five() {
+    // Pivot r1c1 {1,2,3,4,5} with wings r2c2 {1,5}, r3c3 {2,5}, r1c7 {3,5} and r1c8 {4,5}.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [511; 81],
+    };
+    board.candidates[0] = 0b11111;
+    board.candidates[10] = 0b10001;
+    board.candidates[20] = 0b10010;
+    board.candidates[6] = 0b10100;
+    board.candidates[7] = 0b11000;
+
+    let step = logical_solver::wings::find_bent_naked_subset(&board, &|_| true)
+        .expect("Should find a bent naked subset");
+
+    assert_eq!(step.technique, Technique::VWXYZWing);
+    assert_eq!(step.cause.len(), 5);
+    assert!(
+        step.eliminations
+            .iter()
+            .all(|e| e.value == 5 && (e.index == 1 || e.index == 2))
     );
 }
 
 #[test]
-fn test_analyze_difficulty_classification() {
-    let steps = vec![
-        SolvingStep {
-            technique: "Jellyfish".to_string(),
-            placements: vec![],
-            eliminations: vec![],
-            cause: vec![],
-        },
-        SolvingStep {
-            technique: "UniqueRectangleType1".to_string(),
-            placements: vec![],
-            eliminations: vec![],
-            cause: vec![],
-        },
-        SolvingStep {
-            technique: "W-Wing".to_string(),
-            placements: vec![],
-            eliminations: vec![],
-            cause: vec![],
-        },
-        SolvingStep {
-            technique: "UnknownTechnique".to_string(),
-            placements: vec![],
-            eliminations: vec![],
-            cause: vec![],
-        },
-    ];
+fn test_x_chain_detection() {
+    let puzzle_str =
+        "..362..9..2.4..7....5..3...8..1..95..4.....1..56..9..4...8..5....8..4.3..1..672..";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
 
-    let stats = analyze_difficulty(&steps);
+    let step = steps
+        .iter()
+        .find(|s| s.technique == Technique::XChain)
+        .expect("Expected an X-Chain step");
+
+    // A chain alternates strong and weak links, starting and ending with a strong one.
+    assert!(step.cause.len() >= 4);
+    assert_eq!(step.cause.len() % 2, 0);
+    let num = step.cause[0].candidates[0];
+    assert!(step.cause.iter().all(|c| c.candidates == vec![num]));
+    assert!(step.eliminations.iter().all(|e| e.value == num));
+}
 
-    assert_eq!(stats.max_level, TechniqueLevel::Master);
-    assert_eq!(stats.master_count, 3);
+#[test]
+fn test_xy_chain_detection() {
+    let puzzle_str =
+        "..362..9..2.4..7....5..3...8..1..95..4.....1..56..9..4...8..5....8..4.3..1..672..";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
+
+    let step = steps
+        .iter()
+        .find(|s| s.technique == Technique::XYChain)
+        .expect("Expected an XY-Chain step");
+
+    assert!(step.cause.len() >= 3);
+    assert!(step.cause.iter().all(|c| c.candidates.len() == 2));
+
+    // The eliminated digit must appear in both ends of the chain.
+    let value = step.eliminations[0].value;
+    assert!(step.cause[0].candidates.contains(&value));
+    assert!(step.cause[step.cause.len() - 1].candidates.contains(&value));
 }
 
 #[test]
-fn test_hidden_triple_found() {
-    // Construct a logical board where {1, 2, 3} form a Hidden Triple in Row 0.
+fn test_xy_chain_reenters_cell_through_other_digit() {
+    // The chain r1c7-r3c8-r3c7-r3c6-r2c4 passes r3c7 carrying 6, but a shorter
+    // path reaches r3c7 first carrying 5, so visits must be tracked per digit.
+    let puzzle_str =
+        "29..5...46...84...3..2....79..73.8...3...91...46...9.....9.........4.71...8..2...";
+    let board: LogicalBoard = "2 9 17 136 5 1367 36 8 4 6 157 157 13 8 4 235 2359 12359 \
+        3 8 4 2 9 16 56 56 7 9 125 125 7 3 156 8 4 256 8 3 257 4 26 9 1 2567 256 \
+        17 4 6 158 12 158 9 2357 235 147 1267 3 9 167 15678 2456 256 2568 \
+        5 26 9 368 4 368 7 1 2368 147 167 8 1356 167 2 3456 3569 3569"
+        .parse()
+        .unwrap();
+    let mut solution: Board = puzzle_str.parse().unwrap();
+    assert!(solver::solve(&mut solution));
+
+    let step = logical_solver::chains::find_xy_chain(&board, &|_| true)
+        .expect("Expected an XY-Chain step");
+    assert_eq!(step.technique, Technique::XYChain);
+    assert!(!step.eliminations.is_empty());
+    for e in &step.eliminations {
+        assert!(board.candidates[e.index] & (1 << (e.value - 1)) != 0);
+        assert_ne!(solution.cells[e.index], e.value);
+    }
+}
 
+#[test]
+fn test_remote_pair_found() {
+    // {1, 2} pairs at r1c1 - r1c5 - r5c5 - r5c9: the ends hold opposite digits.
     let mut board = LogicalBoard {
         cells: [0; 81],
-        candidates: [0; 81],
+        candidates: [511; 81],
     };
+    for idx in [0, 4, 40, 44] {
+        board.candidates[idx] = 0b11;
+    }
 
-    // Initialize Row 0 indices manually since ROW_UNITS is not pub
-    let row_indices: Vec<usize> = (0..9).collect();
+    let step = logical_solver::chains::find_remote_pairs(&board, &|_| true)
+        .expect("Should find RemotePair");
+
+    assert_eq!(step.technique, Technique::RemotePair);
+    let chain: Vec<usize> = step.cause.iter().map(|c| c.index).collect();
+    assert_eq!(chain, vec![0, 4, 40, 44]);
+
+    // r1c9 and r5c1 see both ends.
+    let mut elims: Vec<(usize, u8)> = step
+        .eliminations
+        .iter()
+        .map(|e| (e.index, e.value))
+        .collect();
+    elims.sort();
+    assert_eq!(elims, vec![(8, 1), (8, 2), (36, 1), (36, 2)]);
+}
+
+#[test]
+fn test_simple_coloring_trap_detection() {
+    let puzzle_str =
+        "..3.8..7..821......7...35...36..5......4.9......8..96...19...5......123..2..5.8..";
+    let steps = solve_preferring(puzzle_str, Technique::SimpleColoringTrap);
+
+    let step = steps
+        .iter()
+        .find(|s| s.technique == Technique::SimpleColoringTrap)
+        .expect("Expected a Simple Coloring Trap step");
+
+    let color_cells = |color: u8| -> Vec<usize> {
+        step.cause
+            .iter()
+            .filter(|c| c.role == Some(CauseRole::Color(color)))
+            .map(|c| c.index)
+            .collect()
+    };
+    let (color_a, color_b) = (color_cells(0), color_cells(1));
+    assert!(!color_a.is_empty() && !color_b.is_empty());
+    assert_eq!(color_a.len() + color_b.len(), step.cause.len());
+
+    // Each trapped cell sees both colors.
+    let sees = |a: usize, b: usize| {
+        a != b && (a / 9 == b / 9 || a % 9 == b % 9 || (a / 27 == b / 27 && a % 9 / 3 == b % 9 / 3))
+    };
+    for elim in &step.eliminations {
+        assert!(color_a.iter().any(|&c| sees(elim.index, c)));
+        assert!(color_b.iter().any(|&c| sees(elim.index, c)));
+    }
+}
+
+#[test]
+fn test_simple_coloring_wrap_detection() {
+    // 1 forms the chain r1c1 - r1c5 - r5c5 - r5c2 - r2c2, so r1c1 and r2c2 share a
+    // color and a box.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [0b110; 81],
+    };
+    for idx in [0, 4, 40, 37, 10, 20] {
+        board.candidates[idx] |= 1;
+    }
+
+    let step = logical_solver::coloring::find_simple_coloring(&board, &|_| true)
+        .expect("Expected a Simple Coloring Wrap step");
+
+    assert_eq!(step.technique, Technique::SimpleColoringWrap);
+    let mut elims: Vec<(usize, u8)> = step
+        .eliminations
+        .iter()
+        .map(|e| (e.index, e.value))
+        .collect();
+    elims.sort();
+    assert_eq!(elims, vec![(0, 1), (10, 1), (40, 1)]);
+}
+
+#[test]
+fn test_multi_coloring_detection() {
+    let puzzle_str =
+        ".4...87.....7...4..52.491..4.3...52..7..3..8..21...4.7..748.21..1...7.....59...7.";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
+
+    let step = steps
+        .iter()
+        .find(|s| s.technique == Technique::MultiColoring)
+        .expect("Expected a Multi-Coloring step");
+
+    // Two components, two colors each.
+    for color in 0..4 {
+        assert!(
+            step.cause
+                .iter()
+                .any(|c| c.role == Some(CauseRole::Color(color)))
+        );
+    }
+}
+
+#[test]
+fn test_3d_medusa_elimination() {
+    let puzzle_str =
+        "..362..9..2.4..7....5..3...8..1..95..4.....1..56..9..4...8..5....8..4.3..1..672..";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
+
+    let step = steps
+        .iter()
+        .find(|s| s.technique == Technique::Medusa3D && s.placements.is_empty())
+        .expect("Expected a 3D Medusa elimination step");
+
+    // Eliminated candidates are never part of the coloring.
+    for elim in &step.eliminations {
+        assert!(
+            !step
+                .cause
+                .iter()
+                .any(|c| c.index == elim.index && c.candidates == vec![elim.value])
+        );
+    }
+}
+
+#[test]
+fn test_3d_medusa_color_contradiction() {
+    let puzzle_str =
+        "9.175.....5..2.....2.9..7.84.9....65.........86....4.31.3..5.7.....6..1.....795.4";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
+
+    let step = steps
+        .iter()
+        .find(|s| s.technique == Technique::Medusa3D && !s.placements.is_empty())
+        .expect("Expected a 3D Medusa placement step");
+
+    // Every placement comes from the surviving color.
+    let placed_roles: Vec<Option<CauseRole>> = step
+        .placements
+        .iter()
+        .map(|p| {
+            step.cause
+                .iter()
+                .find(|c| c.index == p.index && c.candidates == vec![p.value])
+                .expect("Placements must be colored candidates")
+                .role
+                .clone()
+        })
+        .collect();
+    assert!(placed_roles.iter().all(|r| *r == placed_roles[0]));
+}
+
+#[test]
+fn test_aic_type_1_detection() {
+    let puzzle_str =
+        "...5.6....96382...38........7.6....9.39...76.6....8.4........58...82367....7.4...";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
+
+    let step = steps
+        .iter()
+        .find(|s| s.technique == Technique::AicType1)
+        .expect("Expected an AIC Type 1 step");
+
+    // Both ends of the chain carry the eliminated digit.
+    let value = step.eliminations[0].value;
+    assert!(step.cause[0].candidates.contains(&value));
+    assert!(step.cause[step.cause.len() - 1].candidates.contains(&value));
+}
+
+#[test]
+fn test_aic_type_2_detection() {
+    let puzzle_str =
+        "....5.26.....6.7.32....3..8.3...9..562.....898..5...7.3..4....65.4.1.....86.9....";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
+
+    let has_aic = steps.iter().any(|s| s.technique == Technique::AicType2);
+    assert!(has_aic, "Expected AIC Type 2 technique usage");
+}
+
+#[test]
+fn test_discontinuous_nice_loop_placement() {
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [511; 81],
+    };
+
+    // Digit 1 is confined to r1c1/r1c2 in row 1 and to r1c1/r2c1 in column 1.
+    // If r1c1 is not 1, both r1c2 and r2c1 would be 1 inside the same box.
+    for i in 2..9 {
+        board.candidates[i] &= !1;
+    }
+    for r in 2..9 {
+        board.candidates[r * 9] &= !1;
+    }
+
+    let step = logical_solver::aic::find_aic(&board, &|_| true).expect("Should find a nice loop");
+
+    assert_eq!(step.technique, Technique::DiscontinuousNiceLoop);
+    assert_eq!(step.placements.len(), 1);
+    assert_eq!(step.placements[0].index, 0);
+    assert_eq!(step.placements[0].value, 1);
+}
+
+#[test]
+fn test_als_xz_detection() {
+    let puzzle_str =
+        ".8...7..46.759....5...8....81......2...438...7......35....7...9....457.34..1...5.";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
+
+    let step = steps
+        .iter()
+        .find(|s| s.technique == Technique::AlsXz)
+        .expect("Expected an ALS-XZ step");
+
+    // Every cause cell belongs to one of the two sets.
+    assert!(
+        step.cause
+            .iter()
+            .all(|c| matches!(c.role, Some(CauseRole::Als(0 | 1))))
+    );
+    assert!(step.cause.iter().any(|c| c.role == Some(CauseRole::Als(1))));
+    let cause: Vec<usize> = step.cause.iter().map(|c| c.index).collect();
+    assert!(step.eliminations.iter().all(|e| !cause.contains(&e.index)));
+}
+
+#[test]
+fn test_doubly_linked_als_xz_found() {
+    // r1c1 and r1c5 both hold {1, 2}: two one-cell sets linked by both digits.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [511; 81],
+    };
+    board.candidates[0] = 0b11;
+    board.candidates[4] = 0b11;
+
+    let step = logical_solver::als::find_als_xz(&board, &|_| true).expect("Should find an ALS-XZ");
+
+    assert_eq!(step.technique, Technique::DoublyLinkedAlsXz);
+    assert_eq!(step.cause.len(), 2);
+    // Both digits leave the rest of row 1.
+    assert_eq!(step.eliminations.len(), 14);
+    assert!(
+        step.eliminations
+            .iter()
+            .all(|e| e.index < 9 && e.value <= 2)
+    );
+}
+
+#[test]
+fn test_als_xy_wing_detection() {
+    assert_technique_used(
+        ".8...7..46.759....5...8....81......2...438...7......35....7...9....457.34..1...5.",
+        Technique::AlsXyWing,
+    );
+}
+
+#[test]
+fn test_death_blossom_detection() {
+    let puzzle_str =
+        "....92....9.7....5.83..54...5......9...3.1...2......6...71..34.9....7.5....54....";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
+
+    let step = steps
+        .iter()
+        .find(|s| s.technique == Technique::DeathBlossom)
+        .expect("Expected a Death Blossom step");
+
+    // The stem comes first, followed by one petal per stem candidate.
+    let stem = &step.cause[0];
+    assert_eq!(stem.role, None);
+    for petal in 0..stem.candidates.len() as u8 {
+        assert!(
+            step.cause
+                .iter()
+                .any(|c| c.role == Some(CauseRole::Als(petal)))
+        );
+    }
+}
+
+#[test]
+fn test_sue_de_coq_found() {
+    // r1c1 and r1c2 hold {1, 2, 3, 4}; r1c6 holds {1, 2} and r2c1 holds {3, 4}.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [511; 81],
+    };
+    board.candidates[0] = 0b1111;
+    board.candidates[1] = 0b1111;
+    board.candidates[5] = 0b0011;
+    board.candidates[9] = 0b1100;
+
+    let step = logical_solver::intersection::find_sue_de_coq(&board, &|_| true)
+        .expect("Should find SueDeCoq");
+
+    assert_eq!(step.technique, Technique::SueDeCoq);
+    let cause: Vec<usize> = step.cause.iter().map(|c| c.index).collect();
+    assert_eq!(cause, vec![0, 1, 5, 9]);
+
+    // {1, 2} leave the rest of row 1 and {3, 4} the rest of box 1.
+    assert_eq!(step.eliminations.len(), 24);
+    for elim in &step.eliminations {
+        assert!(!cause.contains(&elim.index));
+        if elim.value <= 2 {
+            assert!(elim.index < 9);
+        } else {
+            assert!(elim.value <= 4 && (elim.index / 27 == 0 && elim.index % 9 < 3));
+        }
+    }
+}
+
+#[test]
+fn test_aligned_pair_exclusion_found() {
+    // r1c1 {1,2} and r1c2 {1,2,3} share the bivalue peers r1c3 {1,3} and r2c1 {2,3}.
+    // Both combinations with r1c2 = 3 would empty one of them.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [511; 81],
+    };
+    board.candidates[0] = 0b011;
+    board.candidates[1] = 0b111;
+    board.candidates[2] = 0b101;
+    board.candidates[9] = 0b110;
+
+    let step = logical_solver::exclusion::find_aligned_pair_exclusion(&board, &|_| true)
+        .expect("Should find AlignedPairExclusion");
+    assert_eq!(step.technique, Technique::AlignedPairExclusion);
+    let elims: Vec<(usize, u8)> = step
+        .eliminations
+        .iter()
+        .map(|e| (e.index, e.value))
+        .collect();
+    assert_eq!(elims, vec![(1, 3)]);
+    let cause: Vec<usize> = step.cause.iter().map(|c| c.index).collect();
+    assert_eq!(cause, vec![0, 1, 2, 9]);
+}
+
+#[test]
+fn test_aligned_pair_exclusion_on_cells_that_do_not_see_each_other() {
+    // r1c1 {1,2} and r2c4 {1,2,3} share the bivalue peers r1c4 {1,3} and r2c1 {2,3}.
+    // Every combination with r2c4 = 3 would empty one of them. r1c2 and r1c3 are filled
+    // so that no pair that sees each other makes the same elimination first.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [511; 81],
+    };
+    for (idx, value) in [(1, 8), (2, 9)] {
+        board.cells[idx] = value;
+        board.candidates[idx] = 0;
+    }
+    board.candidates[0] = 0b011;
+    board.candidates[12] = 0b111;
+    board.candidates[3] = 0b101;
+    board.candidates[9] = 0b110;
+
+    let step = logical_solver::exclusion::find_aligned_pair_exclusion(&board, &|_| true)
+        .expect("Should find AlignedPairExclusion");
+    let elims: Vec<(usize, u8)> = step
+        .eliminations
+        .iter()
+        .map(|e| (e.index, e.value))
+        .collect();
+    assert_eq!(elims, vec![(12, 3)]);
+    let cause: Vec<usize> = step.cause.iter().map(|c| c.index).collect();
+    assert_eq!(cause, vec![0, 12, 3, 9]);
+}
+
+#[test]
+fn test_aligned_triple_exclusion_detection() {
+    assert_technique_used(
+        "..97...1......45.9....6..326.....1..18.....27..7.....895..2....3.61......2...53..",
+        Technique::AlignedTripleExclusion,
+    );
+}
+
+#[test]
+fn test_junior_exocet_found() {
+    // Base r1c1 {1,2} and r1c2 {2,3}, targets r2c4 {1,2,4} and r3c7 {1,2,5}. The
+    // companions r3c4 and r2c7 hold no base digit, and column 7 has none of them
+    // outside the band, so every base digit fits in the other two cross-lines.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [511; 81],
+    };
+    board.candidates[0] = 0b011;
+    board.candidates[1] = 0b110;
+    board.candidates[12] = 0b1011;
+    board.candidates[24] = 0b10011;
+    board.candidates[21] = 0b111111000;
+    board.candidates[15] = 0b111111000;
+    for row in 3..9 {
+        board.candidates[row * 9 + 6] &= !0b111;
+    }
+
+    let step = logical_solver::exocet::find_junior_exocet(&board, &|_| true)
+        .expect("Should find JuniorExocet");
+    assert_eq!(step.technique, Technique::JuniorExocet);
+    let cause: Vec<usize> = step.cause.iter().map(|c| c.index).collect();
+    assert_eq!(cause, vec![0, 1, 12, 24]);
+
+    // Non-base digits leave the targets; 3 fits in neither target, so it leaves the base.
+    let elims: Vec<(usize, u8)> = step
+        .eliminations
+        .iter()
+        .map(|e| (e.index, e.value))
+        .collect();
+    assert_eq!(elims, vec![(12, 4), (24, 5), (1, 3)]);
+}
+
+#[test]
+fn test_template_placement_found() {
+    // Digit 1 can only go in r1c1 of row 1, so every template of 1 uses that cell.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [511; 81],
+    };
+    for idx in 1..9 {
+        board.candidates[idx] &= !1;
+    }
+
+    let step =
+        logical_solver::templates::find_templates(&board, &|_| true).expect("Should find template");
+    assert_eq!(step.technique, Technique::TemplatePlacement);
+    let placements: Vec<(usize, u8)> = step.placements.iter().map(|p| (p.index, p.value)).collect();
+    assert_eq!(placements, vec![(0, 1)]);
+    assert_eq!(step.cause.len(), 73);
+
+    // With nothing restricted, every cell is covered by some template.
+    let empty = LogicalBoard {
+        cells: [0; 81],
+        candidates: [511; 81],
+    };
+    assert!(logical_solver::templates::find_templates(&empty, &|_| true).is_none());
+}
+
+#[test]
+fn test_template_elimination_found() {
+    // Digit 1 is confined to c1-c2 in rows 1 and 4, so no template puts it anywhere
+    // else in columns 1-2 or in the c3 cells of boxes 1 and 4.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [511; 81],
+    };
+    for idx in (2..9).chain(29..36) {
+        board.candidates[idx] &= !1;
+    }
+
+    let step =
+        logical_solver::templates::find_templates(&board, &|_| true).expect("Should find template");
+    assert_eq!(step.technique, Technique::TemplateElimination);
+    let elims: Vec<usize> = step.eliminations.iter().map(|e| e.index).collect();
+    let mut expected: Vec<usize> = [1, 2, 4, 5, 6, 7, 8]
+        .iter()
+        .flat_map(|row| [row * 9, row * 9 + 1])
+        .chain([11, 20, 38, 47])
+        .collect();
+    expected.sort();
+    assert_eq!(elims, expected);
+    assert!(step.eliminations.iter().all(|e| e.value == 1));
+}
+
+/// Checks that the cause of a forcing chain is a valid implication tree: every node
+/// points at an earlier one and every root is an assumption.
+fn assert_implication_tree(step: &SolvingStep) {
+    for (pos, cell) in step.cause.iter().enumerate() {
+        match cell.role {
+            Some(CauseRole::Assumption) => {}
+            Some(CauseRole::Implied(ref parents)) | Some(CauseRole::Contradiction(ref parents)) => {
+                assert!(!parents.is_empty(), "Every implied node has a premise");
+                for &parent in parents {
+                    assert!((parent as usize) < pos, "Parent must precede its node")
+                }
+            }
+            _ => panic!("Unexpected role in forcing chain cause"),
+        }
+    }
+}
+
+#[test]
+fn test_forcing_chain_records_every_premise() {
+    // Assuming r1c1 = 1 makes r1c2 = 3, then r1c3 = 4 (its 1 and 3 are both gone),
+    // which leaves r2c1 {3,4} empty.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [511; 81],
+    };
+    board.candidates[0] = 0b0011;
+    board.candidates[1] = 0b0101;
+    board.candidates[2] = 0b1101;
+    board.candidates[9] = 0b1100;
+
+    let step = logical_solver::forcing::find_contradiction_chain(
+        &board,
+        0,
+        1,
+        logical_solver::forcing::MAX_FORCING_DEPTH,
+    )
+    .expect("Expected a contradiction");
+
+    let cause: Vec<(usize, Option<CauseRole>)> = step
+        .cause
+        .iter()
+        .map(|c| (c.index, c.role.clone()))
+        .collect();
+    assert_eq!(
+        cause,
+        vec![
+            (0, Some(CauseRole::Assumption)),
+            (1, Some(CauseRole::Implied(vec![0]))),
+            (2, Some(CauseRole::Implied(vec![0, 1]))),
+            (9, Some(CauseRole::Contradiction(vec![1, 2]))),
+        ]
+    );
+}
+
+#[test]
+fn test_nishio_forcing_chain_found() {
+    let puzzle_str =
+        "..956......74...2.4....9...2...4..3.8..3.2..5.3..9...4...2....8.5...47......516..";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let mut solution = initial_board;
+    assert!(solver::solve(&mut solution));
+
+    let (steps, solved_board) = logical_solver::solve_with_steps(&initial_board);
+    assert_eq!(solved_board.cells, solution.cells);
+
+    let step = steps
+        .iter()
+        .find(|s| s.technique == Technique::NishioForcingChain)
+        .expect("Expected NishioForcingChain technique usage");
+    assert_implication_tree(step);
+    assert_eq!(step.cause[0].role, Some(CauseRole::Assumption));
+    assert!(matches!(
+        step.cause.last().unwrap().role,
+        Some(CauseRole::Contradiction(_))
+    ));
+
+    // The assumed candidate is the one eliminated, and it is not the solution.
+    let elim = &step.eliminations[0];
+    assert_eq!(step.eliminations.len(), 1);
+    assert_eq!(
+        (step.cause[0].index, step.cause[0].candidates[0]),
+        (elim.index, elim.value)
+    );
+    assert_ne!(solution.cells[elim.index], elim.value);
+}
+
+#[test]
+fn test_cell_and_unit_forcing_chains_found() {
+    let puzzle_str =
+        "...49..2...1..5..382......1..6.84....8.....1....93.7..3......429..2..1...1..46...";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let mut solution = initial_board;
+    assert!(solver::solve(&mut solution));
+    let board = LogicalBoard::from_board(&initial_board);
+
+    for step in [
+        logical_solver::forcing::find_cell_forcing_chain(&board, &|_| true)
+            .expect("Should find cell chain"),
+        logical_solver::forcing::find_unit_forcing_chain(&board, &|_| true)
+            .expect("Should find unit chain"),
+    ] {
+        assert_implication_tree(&step);
+        let roots = step
+            .cause
+            .iter()
+            .filter(|c| c.role == Some(CauseRole::Assumption))
+            .count();
+        assert!(roots >= 2, "Every branch must start with an assumption");
+
+        for p in &step.placements {
+            assert_eq!(solution.cells[p.index], p.value);
+            // Like any placement, it removes the digit from its peers.
+            let peers_with_digit = (0..81).filter(|&i| {
+                i != p.index
+                    && board.candidates[i] & (1 << (p.value - 1)) != 0
+                    && (i / 9 == p.index / 9
+                        || i % 9 == p.index % 9
+                        || (i / 27 == p.index / 27 && i % 9 / 3 == p.index % 9 / 3))
+            });
+            for peer in peers_with_digit {
+                assert!(
+                    step.eliminations
+                        .iter()
+                        .any(|e| e.index == peer && e.value == p.value)
+                );
+            }
+        }
+        for e in &step.eliminations {
+            assert_ne!(solution.cells[e.index], e.value);
+        }
+    }
+}
+
+#[test]
+fn test_logical_board_set_cell_returns_false_if_filled() {
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [0; 81],
+    };
+    board.cells[0] = 5;
+    let result = board.set_cell(0, 1);
+    assert!(
+        !result,
+        "set_cell should return false if cell is already filled"
+    );
+}
+
+#[test]
+fn test_analyze_difficulty_classification() {
+    let steps = vec![
+        SolvingStep {
+            technique: Technique::Jellyfish,
+            placements: vec![],
+            eliminations: vec![],
+            cause: vec![],
+        },
+        SolvingStep {
+            technique: Technique::UniqueRectangleType1,
+            placements: vec![],
+            eliminations: vec![],
+            cause: vec![],
+        },
+        SolvingStep {
+            technique: Technique::WWing,
+            placements: vec![],
+            eliminations: vec![],
+            cause: vec![],
+        },
+    ];
+
+    let stats = analyze_difficulty(&steps);
+
+    assert_eq!(stats.max_level, TechniqueLevel::Master);
+    assert_eq!(stats.master_count, 3);
+
+    let ur_steps: Vec<SolvingStep> = [
+        Technique::UniqueRectangleType2,
+        Technique::UniqueRectangleType3,
+        Technique::UniqueRectangleType4,
+        Technique::UniqueRectangleType5,
+        Technique::UniqueRectangleType6,
+        Technique::HiddenUniqueRectangle,
+    ]
+    .iter()
+    .map(|name| SolvingStep {
+        technique: *name,
+        placements: vec![],
+        eliminations: vec![],
+        cause: vec![],
+    })
+    .collect();
+
+    let ur_stats = analyze_difficulty(&ur_steps);
+    assert_eq!(ur_stats.max_level, TechniqueLevel::Master);
+    assert_eq!(ur_stats.master_count, 6);
+
+    let quad_steps: Vec<SolvingStep> = [Technique::NakedQuad, Technique::HiddenQuad]
+        .iter()
+        .map(|name| SolvingStep {
+            technique: *name,
+            placements: vec![],
+            eliminations: vec![],
+            cause: vec![],
+        })
+        .collect();
+
+    let quad_stats = analyze_difficulty(&quad_steps);
+    assert_eq!(quad_stats.max_level, TechniqueLevel::Advanced);
+    assert_eq!(quad_stats.advanced_count, 2);
+
+    let exclusion_steps: Vec<SolvingStep> = [
+        Technique::AlignedPairExclusion,
+        Technique::AlignedTripleExclusion,
+    ]
+    .iter()
+    .map(|name| SolvingStep {
+        technique: *name,
+        placements: vec![],
+        eliminations: vec![],
+        cause: vec![],
+    })
+    .collect();
+
+    let exclusion_stats = analyze_difficulty(&exclusion_steps);
+    assert_eq!(exclusion_stats.max_level, TechniqueLevel::Master);
+    assert_eq!(exclusion_stats.advanced_count, 1);
+    assert_eq!(exclusion_stats.master_count, 1);
+
+    let forcing_steps: Vec<SolvingStep> = [
+        Technique::JuniorExocet,
+        Technique::TemplatePlacement,
+        Technique::TemplateElimination,
+        Technique::NishioForcingChain,
+        Technique::CellForcingChain,
+        Technique::UnitForcingChain,
+    ]
+    .iter()
+    .map(|name| SolvingStep {
+        technique: *name,
+        placements: vec![],
+        eliminations: vec![],
+        cause: vec![],
+    })
+    .collect();
+
+    let forcing_stats = analyze_difficulty(&forcing_steps);
+    assert_eq!(forcing_stats.max_level, TechniqueLevel::Extreme);
+    assert_eq!(forcing_stats.extreme_count, 6);
+}
+
+#[test]
+fn test_hidden_triple_found() {
+    // Construct a logical board where {1, 2, 3} form a Hidden Triple in Row 0.
+
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [0; 81],
+    };
+
+    // Initialize Row 0 indices manually since ROW_UNITS is not pub
+    let row_indices: Vec<usize> = (0..9).collect();
 
     // Set cells 0, 1, 2 to contain {1, 2, 3} + {9}
     // {1,2,3,9} = 1 | 2 | 4 | 256 = 263
@@ -395,8 +1617,8 @@ fn test_hidden_triple_found() {
     // Set other cells in Row 0 to contain only {4, 5, 6, 7, 8} (Mask: 496)
     // {4,5,6,7,8} = 8 | 16 | 32 | 64 | 128 = 248
     let other_mask = 8 | 16 | 32 | 64 | 128;
-    for i in 3..9 {
-        board.candidates[row_indices[i]] = other_mask;
+    for &idx in &row_indices[3..9] {
+        board.candidates[idx] = other_mask;
     }
 
     // Fill the rest of the board with empty/full candidates to avoid interference
@@ -405,12 +1627,474 @@ fn test_hidden_triple_found() {
         board.candidates[i] = 511;
     }
 
-    let step =
-        logical_solver::subsets::find_hidden_triple(&board).expect("Should find HiddenTriple");
+    let step = logical_solver::subsets::find_hidden_triple(&board, &|_| true)
+        .expect("Should find HiddenTriple");
 
-    assert_eq!(step.technique, "HiddenTriple");
+    assert_eq!(step.technique, Technique::HiddenTriple);
     assert_eq!(step.cause.len(), 3);
     // Should eliminate '9' from cells 0, 1, 2
     assert_eq!(step.eliminations.len(), 3);
     assert!(step.eliminations.iter().all(|e| e.value == 9));
 }
+
+#[test]
+fn test_naked_quad_detection() {
+    assert_technique_used(
+        "..461.2......5.87.2....4..9........152.....969........4..7....5.67.3......5.681..",
+        Technique::NakedQuad,
+    );
+}
+
+#[test]
+fn test_hidden_quad_found() {
+    // {1, 2, 3, 4} only appear in cells 0..4 of Row 0, alongside a 9.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [511; 81],
+    };
+
+    let quad_mask = 1 | 2 | 4 | 8 | 256;
+    let other_mask = 16 | 32 | 64 | 128 | 256;
+    for idx in 0..4 {
+        board.candidates[idx] = quad_mask;
+    }
+    for idx in 4..9 {
+        board.candidates[idx] = other_mask;
+    }
+
+    let step = logical_solver::subsets::find_hidden_quad(&board, &|_| true)
+        .expect("Should find HiddenQuad");
+
+    assert_eq!(step.technique, Technique::HiddenQuad);
+    assert_eq!(step.cause.len(), 4);
+    assert!(step.cause.iter().all(|c| c.candidates == vec![1, 2, 3, 4]));
+    // Should eliminate '9' from cells 0..4
+    assert_eq!(step.eliminations.len(), 4);
+    assert!(
+        step.eliminations
+            .iter()
+            .all(|e| e.value == 9 && e.index < 4)
+    );
+}
+
+#[test]
+fn test_technique_ids_are_stable() {
+    assert_eq!(Technique::XWing.id(), "X-Wing");
+    assert_eq!(Technique::BugPlusOne.id(), "BUG+1");
+    assert_eq!(Technique::Medusa3D.id(), "3DMedusa");
+    assert_eq!(Technique::TwoStringKite.name(), "2-String Kite");
+    assert_eq!(Technique::NakedSingle.level(), TechniqueLevel::Basic);
+
+    for technique in Technique::ALL {
+        assert_eq!(Technique::from_id(technique.id()), Some(technique));
+    }
+    assert_eq!(Technique::from_id("UnknownTechnique"), None);
+}
+
+#[test]
+fn test_solver_config_restricts_techniques() {
+    let puzzle_str =
+        "3..6148726148723958723956......86......2.95....6.5...85..9..2...6..2..5.24756.1.9";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let config = SolverConfig {
+        techniques: vec![Technique::NakedSingle, Technique::HiddenSingle],
+        max_level: TechniqueLevel::Extreme,
+    };
+    let (steps, board) = logical_solver::solve_with_config(&initial_board, &config);
+
+    assert!(steps.iter().all(|s| matches!(
+        s.technique,
+        Technique::NakedSingle | Technique::HiddenSingle
+    )));
+    assert!(board.cells.contains(&0), "Singles alone should get stuck");
+}
+
+#[test]
+fn test_solver_config_max_level() {
+    let puzzle_str =
+        "3..6148726148723958723956......86......2.95....6.5...85..9..2...6..2..5.24756.1.9";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let config = SolverConfig::up_to(TechniqueLevel::Intermediate);
+    let (steps, _) = logical_solver::solve_with_config(&initial_board, &config);
+
+    assert!(!steps.is_empty());
+    assert!(
+        steps
+            .iter()
+            .all(|s| s.technique.level() <= TechniqueLevel::Intermediate)
+    );
+}
+
+#[test]
+fn test_extreme_techniques_run_once_master_techniques_stall() {
+    let puzzle_str =
+        "..956......74...2.4....9...2...4..3.8..3.2..5.3..9...4...2....8.5...47......516..";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+
+    let config = SolverConfig::up_to(TechniqueLevel::Master);
+    let (master_steps, stalled_board) = logical_solver::solve_with_config(&initial_board, &config);
+    assert!(
+        stalled_board.cells.contains(&0),
+        "Needs an Extreme technique"
+    );
+
+    let (steps, solved_board) = logical_solver::solve_with_steps(&initial_board);
+    assert!(!solved_board.cells.contains(&0));
+    let first_extreme = steps
+        .iter()
+        .position(|s| s.technique.level() == TechniqueLevel::Extreme)
+        .expect("Expected an Extreme step");
+    let techniques =
+        |steps: &[SolvingStep]| -> Vec<Technique> { steps.iter().map(|s| s.technique).collect() };
+    assert_eq!(
+        techniques(&steps[..first_extreme]),
+        techniques(&master_steps)
+    );
+}
+
+#[test]
+fn test_default_config_tries_patterns_before_forcing_chains() {
+    let config = SolverConfig::default();
+    let position = |technique: Technique| {
+        config
+            .techniques
+            .iter()
+            .position(|&t| t == technique)
+            .expect("Expected a default technique")
+    };
+
+    for pattern in [
+        Technique::JuniorExocet,
+        Technique::TemplatePlacement,
+        Technique::TemplateElimination,
+    ] {
+        assert!(config.allows(pattern));
+        assert!(
+            config
+                .techniques
+                .iter()
+                .filter(|t| t.level() < TechniqueLevel::Extreme)
+                .all(|&t| position(t) < position(pattern))
+        );
+        assert!(position(pattern) < position(Technique::NishioForcingChain));
+    }
+}
+
+#[test]
+fn test_solver_config_order() {
+    let puzzle_str =
+        "...2..7...5..96832.8.7....641.....78.2..745..7.31854....2531..4.3164..5...9...61.";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+
+    let (default_steps, _) =
+        logical_solver::solve_with_config(&initial_board, &SolverConfig::default());
+    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
+    assert_eq!(default_steps.len(), steps.len());
+
+    let config = SolverConfig {
+        techniques: vec![Technique::HiddenSingle, Technique::NakedSingle],
+        max_level: TechniqueLevel::Basic,
+    };
+    let (steps, _) = logical_solver::solve_with_config(&initial_board, &config);
+    assert_eq!(steps[0].technique, Technique::HiddenSingle);
+}
+
+#[test]
+fn test_solver_config_skips_disabled_techniques_of_a_finder() {
+    // Digit 1 forms a Swordfish in rows 1, 4 and 7, digit 2 an X-Wing in rows 2 and 5.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [0b1_1111_1111; 81],
+    };
+    for (row, cols) in [
+        (0, [0, 3]),
+        (3, [3, 6]),
+        (6, [0, 6]),
+        (1, [1, 4]),
+        (4, [1, 4]),
+    ] {
+        let bit = if row % 3 == 0 { 1 } else { 2 };
+        for col in (0..9).filter(|col| !cols.contains(col)) {
+            board.candidates[row * 9 + col] &= !bit;
+        }
+    }
+
+    let both = SolverConfig {
+        techniques: vec![Technique::XWing, Technique::Swordfish],
+        max_level: TechniqueLevel::Advanced,
+    };
+    let step = logical_solver::find_next_step(&board, &both).expect("Expected a Swordfish");
+    assert_eq!(step.technique, Technique::Swordfish);
+
+    let x_wing_only = SolverConfig {
+        techniques: vec![Technique::XWing],
+        max_level: TechniqueLevel::Advanced,
+    };
+    let step = logical_solver::find_next_step(&board, &x_wing_only).expect("Expected an X-Wing");
+    assert_eq!(step.technique, Technique::XWing);
+    assert!(step.eliminations.iter().all(|e| e.value == 2));
+}
+
+#[test]
+fn test_hint_is_never_harder_than_an_advanced_step() {
+    // Digit 1 forms a Jellyfish in rows 1, 4, 7 and 8, digit 2 an X-Wing in rows 2 and 5.
+    // The fish finder meets the Jellyfish first, but the X-Wing is simpler.
+    let mut board = LogicalBoard {
+        cells: [0; 81],
+        candidates: [0b1_1111_1111; 81],
+    };
+    for (row, bit, cols) in [
+        (0, 1, [0, 3]),
+        (3, 1, [3, 6]),
+        (6, 1, [6, 8]),
+        (7, 1, [0, 8]),
+        (1, 2, [1, 4]),
+        (4, 2, [1, 4]),
+    ] {
+        for col in (0..9).filter(|col| !cols.contains(col)) {
+            board.candidates[row * 9 + col] &= !bit;
+        }
+    }
+
+    let jellyfish_first = SolverConfig {
+        techniques: vec![Technique::Jellyfish, Technique::XWing],
+        max_level: TechniqueLevel::Master,
+    };
+    let hint = logical_solver::find_next_step(&board, &jellyfish_first).expect("Expected a hint");
+    assert_eq!(hint.technique, Technique::XWing);
+    assert!(hint.eliminations.iter().all(|e| e.value == 2));
+
+    let advanced =
+        logical_solver::find_next_step(&board, &SolverConfig::up_to(TechniqueLevel::Advanced))
+            .expect("Expected an Advanced step");
+    let hint =
+        logical_solver::find_next_step(&board, &SolverConfig::default()).expect("Expected a hint");
+    assert!(hint.technique.level() <= advanced.technique.level());
+}
+
+#[test]
+fn test_hint_from_player_candidates() {
+    let empty: Board = ".".repeat(81).parse().unwrap();
+    let mut masks = [0u16; 81];
+    masks[40] = 1 << 4;
+    let board = LogicalBoard::from_candidates(&empty, &masks).unwrap();
+
+    let step =
+        logical_solver::find_next_step(&board, &SolverConfig::default()).expect("Expected a hint");
+    assert_eq!(step.technique, Technique::NakedSingle);
+    assert_eq!(step.placements[0].index, 40);
+    assert_eq!(step.placements[0].value, 5);
+    // The player has not removed the 5s around it yet.
+    assert_eq!(step.eliminations.len(), 20);
+}
+
+#[test]
+fn test_player_candidates_must_be_possible() {
+    let mut board: Board = ".".repeat(81).parse().unwrap();
+    board.cells[0] = 1;
+
+    // r1c2 is only marked with the 1 placed next to it.
+    let mut masks = [0u16; 81];
+    masks[1] = 1 << 0;
+    let err = LogicalBoard::from_candidates(&board, &masks).err().unwrap();
+    assert!(err.contains("cell 1"), "{}", err);
+
+    // No cell of row 2 is marked with 9.
+    let mut masks = [0u16; 81];
+    masks[9..18].fill(0b0_1111_1111);
+    let err = LogicalBoard::from_candidates(&board, &masks).err().unwrap();
+    assert!(err.contains("9 has no place left in row 2"), "{}", err);
+}
+
+#[test]
+fn test_hint_without_marks_matches_solver() {
+    let puzzle_str =
+        "...2..7...5..96832.8.7....641.....78.2..745..7.31854....2531..4.3164..5...9...61.";
+    let initial_board: Board = puzzle_str.parse().unwrap();
+    let board = LogicalBoard::from_candidates(&initial_board, &[0; 81]).unwrap();
+    assert!(board == LogicalBoard::from_board(&initial_board));
+
+    let (steps, _) = logical_solver::solve_with_steps(&initial_board);
+    let hint = logical_solver::find_next_step(&board, &SolverConfig::default()).unwrap();
+    assert_eq!(hint.technique, steps[0].technique);
+    assert_eq!(hint.placements[0].index, steps[0].placements[0].index);
+    assert_eq!(hint.eliminations.len(), steps[0].eliminations.len());
+}
+
+/// Formats a board as a HoDoKu-style pencilmark grid.
+fn to_candidate_grid(board: &LogicalBoard) -> String {
+    let mut grid = String::from(".---------+---------+---------.\n");
+    for row in 0..9 {
+        for col in 0..9 {
+            let idx = row * 9 + col;
+            if col % 3 == 0 {
+                grid.push_str("| ");
+            }
+            let cell: String = match board.cells[idx] {
+                0 => (1..=9)
+                    .filter(|d| board.candidates[idx] & (1 << (d - 1)) != 0)
+                    .map(|d| char::from(b'0' + d))
+                    .collect(),
+                v => v.to_string(),
+            };
+            grid.push_str(&format!("{:<10}", cell));
+        }
+        grid.push_str("|\n");
+    }
+    grid.push_str("'---------+---------+---------'\n");
+    grid
+}
+
+#[test]
+fn test_parse_candidate_grid() {
+    let puzzle_str =
+        "...2..7...5..96832.8.7....641.....78.2..745..7.31854....2531..4.3164..5...9...61.";
+    let mut board = board_from_str(puzzle_str);
+    board.candidates[0] &= !(1 << 0);
+
+    let parsed: LogicalBoard = to_candidate_grid(&board).parse().unwrap();
+    assert!(parsed == board, "The grid should round-trip");
+}
+
+#[test]
+fn test_parse_candidate_grid_errors() {
+    let puzzle_str =
+        "...2..7...5..96832.8.7....641.....78.2..745..7.31854....2531..4.3164..5...9...61.";
+    let board = board_from_str(puzzle_str);
+    let parse = |b: &LogicalBoard| to_candidate_grid(b).parse::<LogicalBoard>();
+
+    let err = "1 2 3".parse::<LogicalBoard>().err().unwrap();
+    assert!(err.contains("expected 81 cells"), "{}", err);
+
+    // A candidate taken by a placed peer.
+    let mut conflict = board;
+    conflict.candidates[0] |= 1 << 1;
+    let err = LogicalBoard::from_candidate_grid(conflict.cells, conflict.candidates)
+        .err()
+        .unwrap();
+    assert!(err.contains("2 at cell 0"), "{}", err);
+
+    // A digit removed from every cell of a unit.
+    let mut no_place = board;
+    for &idx in &[0, 1, 2, 4, 5, 7, 8] {
+        no_place.candidates[idx] &= !(1 << 0);
+    }
+    let err = parse(&no_place).err().unwrap();
+    assert!(err.contains("1 has no place left in row 1"), "{}", err);
+
+    // Placed digits that clash.
+    let mut clash = board;
+    clash.cells[0] = 2;
+    clash.candidates[0] = 0;
+    assert!(parse(&clash).is_err());
+}
+
+#[test]
+fn test_explain_candidate() {
+    let puzzle_str =
+        "...2..7...5..96832.8.7....641.....78.2..745..7.31854....2531..4.3164..5...9...61.";
+    let board = board_from_str(puzzle_str);
+
+    let explanation = explain_candidate(&board, 9, 1).unwrap();
+    assert!(matches!(explanation, CandidateExplanation::Correct));
+
+    // The Naked Single placing 1 in r2c1 removes it from r1c1.
+    match explain_candidate(&board, 0, 1).unwrap() {
+        CandidateExplanation::Eliminated(step) => {
+            assert_eq!(step.technique, Technique::NakedSingle);
+            assert_eq!(step.placements[0].index, 9);
+        }
+        _ => panic!("Expected an eliminating step"),
+    }
+
+    assert!(
+        explain_candidate(&board, 3, 1).is_err(),
+        "The cell is solved"
+    );
+    assert!(
+        explain_candidate(&board, 0, 2).is_err(),
+        "2 is not a candidate"
+    );
+}
+
+#[test]
+fn test_explain_candidate_ignores_wrong_marks() {
+    let puzzle_str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+    let mut board = board_from_str(puzzle_str);
+    // The player wrongly removed 4 from row 1 except r1c6, a Hidden Single that would
+    // remove the correct 4 from r6c6.
+    for idx in (0..9).filter(|&idx| idx != 5) {
+        board.candidates[idx] &= !(1 << 3);
+    }
+
+    let explanation = explain_candidate(&board, 50, 4).unwrap();
+    assert!(matches!(explanation, CandidateExplanation::Correct));
+}
+
+#[test]
+fn test_explain_candidate_searches_every_instance() {
+    let puzzle_str =
+        "...2..7...5..96832.8.7....641.....78.2..745..7.31854....2531..4.3164..5...9...61.";
+    let board = board_from_str(puzzle_str);
+    let first = logical_solver::basic::find_naked_single(&board, &|_| true).unwrap();
+    let first = &first.placements[0];
+    let sees = |a: usize, b: usize| {
+        a / 9 == b / 9 || a % 9 == b % 9 || (a / 27 == b / 27 && a % 9 / 3 == b % 9 / 3)
+    };
+
+    // A candidate in the row of a later Naked Single that the first one leaves alone.
+    let (target, value) = (0..81)
+        .filter(|&i| {
+            i != first.index && board.cells[i] == 0 && board.candidates[i].count_ones() == 1
+        })
+        .flat_map(|i| {
+            let value = board.candidates[i].trailing_zeros() as u8 + 1;
+            (i / 9 * 9..i / 9 * 9 + 9).map(move |t| (i, t, value))
+        })
+        .find(|&(i, t, value)| {
+            t != i
+                && board.cells[t] == 0
+                && board.candidates[t] & (1 << (value - 1)) != 0
+                && (first.value != value || !sees(first.index, t))
+        })
+        .map(|(_, t, value)| (t, value))
+        .expect("Expected a second Naked Single with a candidate to remove");
+
+    match explain_candidate(&board, target, value).unwrap() {
+        CandidateExplanation::Eliminated(step) => {
+            assert_eq!(step.technique, Technique::NakedSingle);
+            assert_ne!(step.placements[0].index, first.index);
+        }
+        _ => panic!("Expected an eliminating step"),
+    }
+}
+
+#[test]
+fn test_explain_candidate_with_chain() {
+    let puzzle_str =
+        "..956......74...2.4....9...2...4..3.8..3.2..5.3..9...4...2....8.5...47......516..";
+    let board = board_from_str(puzzle_str);
+    let mut solution: Board = puzzle_str.parse().unwrap();
+    assert!(solver::solve(&mut solution));
+
+    let mut chains = 0;
+    for idx in (0..81).filter(|&idx| board.cells[idx] == 0) {
+        for value in (1..=9u8).filter(|&v| board.candidates[idx] & (1 << (v - 1)) != 0) {
+            let explanation = explain_candidate(&board, idx, value).unwrap();
+            match explanation {
+                CandidateExplanation::Correct => assert_eq!(solution.cells[idx], value),
+                CandidateExplanation::Eliminated(step) => {
+                    assert_ne!(solution.cells[idx], value);
+                    if step.technique == Technique::NishioForcingChain {
+                        assert_eq!(step.eliminations[0].index, idx);
+                        assert_eq!(step.eliminations[0].value, value);
+                        chains += 1;
+                    }
+                }
+                CandidateExplanation::Unexplained => assert_ne!(solution.cells[idx], value),
+            }
+        }
+    }
+    assert!(chains > 0, "Expected some candidates to need a chain");
+}
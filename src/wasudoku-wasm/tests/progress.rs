@@ -0,0 +1,126 @@
+/*
+* Copyright (C) 2025-2026  Henrique Almeida
+* This file is part of WASudoku.
+*
+* WASudoku is free software: you can redistribute it and/or modify
+* it under the terms of the GNU Affero General Public License as published
+* by the Free Software Foundation, either version 3 of the License, or
+* (at your option) any later version.
+*
+* WASudoku is distributed in the hope that it will be useful,
+* but WITHOUT ANY WARRANTY; without even the implied warranty of
+* MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+* GNU Affero General Public License for more details.
+*
+* You should have received a copy of the GNU Affero General Public License
+* along with WASudoku.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use wasudoku_wasm::board::Board;
+use wasudoku_wasm::logical_solver::{self, LogicalBoard};
+use wasudoku_wasm::progress::check_progress;
+
+const PUZZLE: &str =
+    "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+const SOLUTION: &str =
+    "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+fn digits(s: &str) -> [u8; 81] {
+    let mut cells = [0; 81];
+    for (cell, c) in cells.iter_mut().zip(s.chars()) {
+        *cell = c.to_digit(10).unwrap_or(0) as u8;
+    }
+    cells
+}
+
+#[test]
+fn test_check_progress_without_mistakes() {
+    let puzzle: Board = PUZZLE.parse().unwrap();
+    let mut entries = puzzle.cells;
+    // Two correct entries.
+    entries[2] = 4;
+    entries[3] = 6;
+    let masks = LogicalBoard::from_board(&Board { cells: entries }).candidates;
+
+    let report = check_progress(&puzzle, &entries, &masks).unwrap();
+    assert!(report.wrong_placements.is_empty());
+    assert!(report.wrong_eliminations.is_empty());
+    assert_eq!(report.first_divergent_step, None);
+}
+
+#[test]
+fn test_check_progress_reports_mistakes() {
+    let puzzle: Board = PUZZLE.parse().unwrap();
+    let solution = digits(SOLUTION);
+    let mut entries = puzzle.cells;
+    entries[2] = 2; // The solution has 4.
+    let mut masks = LogicalBoard::from_board(&puzzle).candidates;
+    masks[3] &= !(1 << (solution[3] - 1));
+    // An unmarked cell is not checked.
+    masks[5] = 0;
+
+    let report = check_progress(&puzzle, &entries, &masks).unwrap();
+    assert_eq!(report.wrong_placements.len(), 1);
+    assert_eq!(report.wrong_placements[0].index, 2);
+    assert_eq!(report.wrong_placements[0].value, 2);
+    assert_eq!(report.wrong_eliminations.len(), 1);
+    assert_eq!(report.wrong_eliminations[0].index, 3);
+    assert_eq!(report.wrong_eliminations[0].value, solution[3]);
+
+    // The solve eliminates the wrong digit from its cell, or places the solution
+    // digit the marks lost, or places the wrong digit in a peer.
+    let (steps, _) = logical_solver::solve_with_steps(&puzzle);
+    let step = &steps[report
+        .first_divergent_step
+        .expect("Expected a divergent step")];
+    let sees_cell_2 = |idx: usize| idx / 9 == 0 || idx % 9 == 2 || (idx / 27 == 0 && idx % 9 < 3);
+    let contradicted = step
+        .eliminations
+        .iter()
+        .any(|e| e.index == 2 && e.value == 2)
+        || step.placements.iter().any(|p| {
+            (p.index == 2 && p.value == 4)
+                || (p.index == 3 && p.value == solution[3])
+                || (p.value == 2 && p.index != 2 && sees_cell_2(p.index))
+        });
+    assert!(contradicted);
+}
+
+#[test]
+fn test_check_progress_divergence_follows_the_marks() {
+    let puzzle: Board = PUZZLE.parse().unwrap();
+    let solution = digits(SOLUTION);
+    let (steps, _) = logical_solver::solve_with_steps(&puzzle);
+    let placed_at = |index: usize| {
+        steps
+            .iter()
+            .position(|s| s.placements.iter().any(|p| p.index == index))
+            .unwrap()
+    };
+    let first = steps[0].placements[0].index;
+    let last = steps.last().unwrap().placements[0].index;
+
+    // Earlier steps rely on the last cell, but only placing its digit contradicts
+    // the marks.
+    let mut masks = LogicalBoard::from_board(&puzzle).candidates;
+    masks[last] &= !(1 << (solution[last] - 1));
+    let report = check_progress(&puzzle, &puzzle.cells, &masks).unwrap();
+    assert_eq!(report.first_divergent_step, Some(placed_at(last)));
+    assert!(placed_at(last) > 0);
+
+    // A naked single would be left unmarked, so mark every other digit.
+    masks[first] = 0b1_1111_1111 & !(1 << (solution[first] - 1));
+    let report = check_progress(&puzzle, &puzzle.cells, &masks).unwrap();
+    assert_eq!(report.first_divergent_step, Some(0));
+}
+
+#[test]
+fn test_check_progress_errors() {
+    let puzzle: Board = PUZZLE.parse().unwrap();
+    let mut entries = puzzle.cells;
+    entries[0] = 1;
+    assert!(check_progress(&puzzle, &entries, &[0; 81]).is_err());
+
+    let empty: Board = ".".repeat(81).parse().unwrap();
+    assert!(check_progress(&empty, &empty.cells, &[0; 81]).is_err());
+}